@@ -0,0 +1,347 @@
+use std::path::PathBuf;
+
+use crate::types::{CheckCategory, CheckResult, CheckStatus, Evidence};
+
+use super::host::{self, VolumeSpace};
+use super::sync_check;
+use super::{Check, ScanContext};
+
+pub struct DiskSpaceCheck;
+
+impl Check for DiskSpaceCheck {
+    fn id(&self) -> &'static str {
+        "disk_space"
+    }
+
+    fn label(&self) -> &'static str {
+        "Disk Space"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::System
+    }
+
+    /// Needs a Windows system to measure, but not a working OneNote: the
+    /// install is only used to find its cache.
+    fn depends_on(&self) -> &'static [&'static str] {
+        &["windows_os"]
+    }
+
+    fn run(&self, ctx: &ScanContext) -> CheckResult {
+        check(ctx)
+    }
+}
+
+/// Office version of Click-to-Run installs, which have no `InstallRoot` key.
+const CLICK_TO_RUN_VERSION: &str = "16.0";
+
+/// Catalog keys of the Windows storage settings page.
+const STORAGE_MENU: &[&str] = &["menu.settings", "menu.system", "menu.storage"];
+
+/// Where the export needs room. The export writes the notebooks once, and
+/// Joplin copies them into its profile on import.
+struct Destination {
+    /// Catalog key naming the destination in messages.
+    name: &'static str,
+    path: PathBuf,
+    volume: Option<VolumeSpace>,
+}
+
+pub fn check(ctx: &ScanContext) -> CheckResult {
+    let host = ctx.host();
+    let version = ctx
+        .onenote_install()
+        .map_or(CLICK_TO_RUN_VERSION, |install| install.version.as_str());
+    let mut evidence = Vec::new();
+
+    // What OneNote keeps on this machine is a lower bound of what will be
+    // exported: notebooks only in the cloud are not counted, and the
+    // inventory has no sizes to add, as `GetHierarchy` reports none.
+    let mut sources = Vec::new();
+    if let Some(local) = host.env_var("LOCALAPPDATA") {
+        let cache = PathBuf::from(local).join("Microsoft").join("OneNote").join(version).join("cache");
+        sources.push(("OneNoteCache", cache));
+    }
+    if let Some(profile) = host.env_var("USERPROFILE") {
+        let notebooks = PathBuf::from(profile).join("Documents").join("OneNote Notebooks");
+        sources.push(("LocalNotebooks", notebooks));
+    }
+    let mut estimate = None;
+    for (key, path) in &sources {
+        if let Some(size) = host.dir_size(path) {
+            evidence.push(Evidence::new(*key, size.to_string(), path.to_string_lossy()));
+            *estimate.get_or_insert(0) += size;
+        }
+    }
+    let Some(estimate) = estimate else {
+        let searched: Vec<String> = sources.iter().map(|(_, p)| p.to_string_lossy().into_owned()).collect();
+        return CheckResult {
+            id: "disk_space".into(),
+            label: "Disk Space".into(),
+            status: CheckStatus::Warning,
+            message: ctx.t("disk_space.no_data", &[("paths", &searched.join(", "))]),
+            remediation: vec![ctx.locale().instruction("disk_space.no_data.remediation", &[])],
+            evidence,
+            ..Default::default()
+        };
+    };
+    let partial = if sync_check::full_download_enabled(ctx, version) {
+        String::new()
+    } else {
+        ctx.t("disk_space.partial", &[])
+    };
+
+    let destinations: Vec<Destination> = [
+        ("disk_space.export_folder", export_folder(ctx)),
+        (
+            "disk_space.joplin_profile",
+            host.env_var("APPDATA").map(|appdata| PathBuf::from(appdata).join("joplin-desktop")),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(name, path)| path.map(|path| (name, path)))
+    .map(|(name, path)| Destination {
+        name,
+        volume: host.volume_space(&path),
+        path,
+    })
+    .collect();
+
+    if let Some(unknown) = destinations.iter().find(|d| d.volume.is_none()) {
+        return CheckResult {
+            id: "disk_space".into(),
+            label: "Disk Space".into(),
+            status: CheckStatus::Warning,
+            message: ctx.t(
+                "disk_space.unreadable",
+                &[("place", &ctx.t(unknown.name, &[])), ("path", &unknown.path.to_string_lossy())],
+            ),
+            remediation: vec![ctx.locale().menu(STORAGE_MENU)],
+            evidence,
+            ..Default::default()
+        };
+    }
+
+    // Destinations on the same volume share its free space.
+    let mut volumes: Vec<(&VolumeSpace, Vec<&Destination>)> = Vec::new();
+    for destination in &destinations {
+        let volume = destination.volume.as_ref().expect("checked above");
+        evidence.push(Evidence::new("FreeSpace", volume.free.to_string(), destination.path.to_string_lossy()));
+        match volumes.iter_mut().find(|(v, _)| v.root.eq_ignore_ascii_case(&volume.root)) {
+            Some((_, on_volume)) => on_volume.push(destination),
+            None => volumes.push((volume, vec![destination])),
+        }
+    }
+
+    let headroom = u64::from(ctx.policy().disk.headroom_percent);
+    let mut status = CheckStatus::Pass;
+    let mut details = Vec::new();
+    let mut remediation = Vec::new();
+    for (volume, on_volume) in &volumes {
+        let needed = estimate.saturating_mul(on_volume.len() as u64);
+        let wanted = needed.saturating_add(needed.saturating_mul(headroom) / 100);
+        let places: Vec<String> = on_volume.iter().map(|d| ctx.t(d.name, &[])).collect();
+        let places = places.join(&ctx.t("disk_space.and", &[]));
+        details.push(ctx.t(
+            "disk_space.volume",
+            &[
+                ("volume", &volume.root),
+                ("free", &format_size(volume.free)),
+                ("needed", &format_size(needed)),
+                ("places", &places),
+            ],
+        ));
+        if volume.free < wanted {
+            if volume.free < needed {
+                status = CheckStatus::Fail;
+            } else if status == CheckStatus::Pass {
+                status = CheckStatus::Warning;
+            }
+            remediation.push(ctx.locale().instruction(
+                "disk_space.free_up",
+                &[("size", &format_size(wanted - volume.free)), ("volume", &volume.root)],
+            ));
+        }
+    }
+
+    let key = match status {
+        CheckStatus::Pass => "disk_space.enough",
+        CheckStatus::Warning => "disk_space.tight",
+        _ => "disk_space.not_enough",
+    };
+    if !remediation.is_empty() {
+        remediation.push(ctx.locale().instruction("disk_space.move_export", &[]));
+        remediation.push(ctx.locale().menu(STORAGE_MENU));
+    }
+    CheckResult {
+        id: "disk_space".into(),
+        label: "Disk Space".into(),
+        status,
+        message: ctx.t(
+            key,
+            &[
+                ("estimate", &format_size(estimate)),
+                ("volumes", &details.join("; ")),
+                ("partial", &partial),
+            ],
+        ),
+        remediation,
+        evidence,
+        ..Default::default()
+    }
+}
+
+/// The export folder from the policy, else the user's Documents folder.
+fn export_folder(ctx: &ScanContext) -> Option<PathBuf> {
+    match &ctx.policy().disk.export_folder {
+        Some(folder) => Some(PathBuf::from(host::expand_env(ctx.host(), folder))),
+        None => ctx
+            .host()
+            .env_var("USERPROFILE")
+            .map(|profile| PathBuf::from(profile).join("Documents")),
+    }
+}
+
+/// E.g. "3.4 GB", in binary units as Explorer shows them.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["bytes", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::checks::fixtures::context;
+    use crate::checks::host::FakeHost;
+    use crate::checks::policy::Policy;
+    use crate::checks::registry::FakeRegistry;
+    use crate::types::RemediationStep;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+    const PROFILE: &str = "C:\\Users\\jdoe";
+    const APPDATA: &str = "C:\\Users\\jdoe\\AppData\\Roaming";
+
+    fn notebooks() -> PathBuf {
+        Path::new(PROFILE).join("Documents").join("OneNote Notebooks")
+    }
+
+    fn volume(root: &str, free: u64) -> VolumeSpace {
+        VolumeSpace {
+            root: root.into(),
+            free,
+            total: 500 * GB,
+        }
+    }
+
+    /// A user with `size` bytes of notebooks, exporting to Documents on
+    /// `export` and importing into a Joplin profile on `joplin`.
+    fn host(size: Option<u64>, export: Option<VolumeSpace>, joplin: Option<VolumeSpace>) -> FakeHost {
+        let mut host = FakeHost::new().with_env("USERPROFILE", PROFILE).with_env("APPDATA", APPDATA);
+        if let Some(size) = size {
+            host = host.with_dir_size(notebooks(), size);
+        }
+        if let Some(export) = export {
+            host = host.with_volume(Path::new(PROFILE).join("Documents"), export);
+        }
+        if let Some(joplin) = joplin {
+            host = host.with_volume(Path::new(APPDATA).join("joplin-desktop"), joplin);
+        }
+        host
+    }
+
+    fn run(host: FakeHost) -> CheckResult {
+        check(&context(FakeRegistry::new(), host))
+    }
+
+    #[test]
+    fn passes_with_room_to_spare() {
+        let result = run(host(Some(10 * GB), Some(volume("C:\\", 12 * GB)), Some(volume("D:\\", 100 * GB))));
+        assert_eq!(result.status, CheckStatus::Pass, "{}", result.message);
+        assert!(result.message.starts_with("Enough space for at least 10.0 GB of notebooks"));
+        assert!(result.remediation.is_empty());
+        let keys: Vec<_> = result.evidence.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, ["LocalNotebooks", "FreeSpace", "FreeSpace"]);
+    }
+
+    #[test]
+    fn warns_when_the_headroom_is_not_there() {
+        let result = run(host(Some(10 * GB), Some(volume("C:\\", 11 * GB)), Some(volume("D:\\", 100 * GB))));
+        assert_eq!(result.status, CheckStatus::Warning, "{}", result.message);
+        assert_eq!(
+            result.remediation[0],
+            RemediationStep::Instruction {
+                text: "Free up at least 1.0 GB on C:\\.".into()
+            }
+        );
+        assert_eq!(result.remediation.len(), 3);
+    }
+
+    #[test]
+    fn headroom_comes_from_the_policy() {
+        let mut policy = Policy::default();
+        policy.disk.headroom_percent = 5;
+        let host = host(Some(10 * GB), Some(volume("C:\\", 11 * GB)), Some(volume("D:\\", 100 * GB)));
+        let ctx = context(FakeRegistry::new(), host).with_policy(Arc::new(policy));
+        assert_eq!(check(&ctx).status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn fails_without_room_for_the_estimate() {
+        let result = run(host(Some(10 * GB), Some(volume("C:\\", 9 * GB)), Some(volume("D:\\", 100 * GB))));
+        assert_eq!(result.status, CheckStatus::Fail, "{}", result.message);
+        assert!(result.message.contains("C:\\ has 9.0 GB free and needs 10.0 GB for the export folder"));
+    }
+
+    #[test]
+    fn a_shared_volume_needs_room_for_both_copies() {
+        let result = run(host(Some(10 * GB), Some(volume("C:\\", 15 * GB)), Some(volume("c:\\", 15 * GB))));
+        assert_eq!(result.status, CheckStatus::Fail, "{}", result.message);
+        assert!(
+            result
+                .message
+                .contains("C:\\ has 15.0 GB free and needs 20.0 GB for the export folder and the Joplin profile"),
+            "{}",
+            result.message
+        );
+    }
+
+    #[test]
+    fn warns_without_local_notebook_data() {
+        let result = run(host(None, Some(volume("C:\\", 100 * GB)), Some(volume("C:\\", 100 * GB))));
+        assert_eq!(result.status, CheckStatus::Warning);
+        assert!(result.message.starts_with("No local OneNote data found"), "{}", result.message);
+        assert!(result.message.contains(&notebooks().to_string_lossy().into_owned()));
+        assert!(result.evidence.is_empty());
+    }
+
+    #[test]
+    fn warns_when_a_volume_cannot_be_read() {
+        let result = run(host(Some(10 * GB), Some(volume("C:\\", 100 * GB)), None));
+        assert_eq!(result.status, CheckStatus::Warning);
+        assert!(
+            result.message.starts_with("Could not read the free space for the Joplin profile"),
+            "{}",
+            result.message
+        );
+    }
+
+    #[test]
+    fn sizes_are_shown_in_binary_units() {
+        assert_eq!(format_size(512), "512 bytes");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(10 * GB), "10.0 GB");
+    }
+}
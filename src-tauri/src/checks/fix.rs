@@ -0,0 +1,294 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{CheckStatus, ScanError};
+
+use super::registry::{Hive, RegValue, RegistryWriter};
+use super::{CheckRegistry, ScanContext};
+
+const JOURNAL_FILE_NAME: &str = "fix-journal.json";
+
+/// A single registry write that resolves a check, offered through
+/// [`super::Check::fix`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryFix {
+    pub hive: Hive,
+    pub path: String,
+    pub name: String,
+    pub value: RegValue,
+    /// What the change does, in the user's terms.
+    pub description: String,
+}
+
+/// A fix as it was (or, on a dry run, would be) applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixChange {
+    pub check_id: String,
+    pub fix: RegistryFix,
+    /// Value before the fix, `None` if it was not set.
+    pub previous: Option<RegValue>,
+    /// Whether the registry already held the fixed value.
+    pub unchanged: bool,
+}
+
+/// Applied changes, oldest first, persisted so fixes can be undone in a later
+/// session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub check_id: String,
+    pub hive: Hive,
+    pub path: String,
+    pub name: String,
+    pub previous: Option<RegValue>,
+    pub applied: RegValue,
+    pub applied_at: String,
+}
+
+/// Undo journal stored as JSON in the app data directory.
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    pub fn in_dir(dir: &Path) -> Self {
+        Self {
+            path: dir.join(JOURNAL_FILE_NAME),
+        }
+    }
+
+    pub fn entries(&self) -> Result<Vec<JournalEntry>, ScanError> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let json = std::fs::read_to_string(&self.path)
+            .map_err(|e| ScanError::Unexpected(format!("Failed to read fix journal: {}", e)))?;
+        serde_json::from_str(&json)
+            .map_err(|e| ScanError::Unexpected(format!("Invalid fix journal: {}", e)))
+    }
+
+    fn save(&self, entries: &[JournalEntry]) -> Result<(), ScanError> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                ScanError::Unexpected(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+        let json = serde_json::to_string_pretty(entries)
+            .map_err(|e| ScanError::Unexpected(format!("Failed to serialize fix journal: {}", e)))?;
+        std::fs::write(&self.path, json)
+            .map_err(|e| ScanError::Unexpected(format!("Failed to write fix journal: {}", e)))
+    }
+}
+
+/// Apply the fix offered by check `id` in its current state, or only describe
+/// it when `dry_run` is set. The check runs first, and only a failing or
+/// warning check is changed.
+pub fn fix_check(
+    checks: &CheckRegistry,
+    ctx: &Arc<ScanContext>,
+    reg: &mut dyn RegistryWriter,
+    journal: &Journal,
+    id: &str,
+    dry_run: bool,
+) -> Result<FixChange, ScanError> {
+    let check = checks
+        .get(id)
+        .ok_or_else(|| ScanError::Unexpected(format!("Unknown check '{}'", id)))?;
+    let result = super::run_check(checks, id, ctx)?;
+    if !matches!(result.status, CheckStatus::Fail | CheckStatus::Warning) {
+        return Err(ScanError::Unexpected(format!(
+            "'{}' has nothing to fix: {}",
+            check.label(),
+            result.message
+        )));
+    }
+    let fix = check.fix(ctx).ok_or_else(|| {
+        ScanError::Unexpected(format!("No automatic fix is available for '{}'", check.label()))
+    })?;
+    apply(id, fix, reg, journal, dry_run)
+}
+
+/// Apply `fix` for `check_id`, or only describe it when `dry_run` is set.
+///
+/// The previous value is journaled before the registry is touched, so a
+/// change can always be reverted, even if the app exits right after writing.
+pub fn apply(
+    check_id: &str,
+    fix: RegistryFix,
+    reg: &mut dyn RegistryWriter,
+    journal: &Journal,
+    dry_run: bool,
+) -> Result<FixChange, ScanError> {
+    let previous = reg.read_value(fix.hive, &fix.path, &fix.name);
+    let unchanged = previous.as_ref() == Some(&fix.value);
+    let change = FixChange {
+        check_id: check_id.into(),
+        fix,
+        previous,
+        unchanged,
+    };
+    if dry_run || unchanged {
+        return Ok(change);
+    }
+
+    let mut entries = journal.entries()?;
+    entries.push(JournalEntry {
+        check_id: change.check_id.clone(),
+        hive: change.fix.hive,
+        path: change.fix.path.clone(),
+        name: change.fix.name.clone(),
+        previous: change.previous.clone(),
+        applied: change.fix.value.clone(),
+        applied_at: chrono::Utc::now().to_rfc3339(),
+    });
+    journal.save(&entries)?;
+
+    let fix = &change.fix;
+    if let Err(e) = reg.write_value(fix.hive, &fix.path, &fix.name, &fix.value) {
+        entries.pop();
+        journal.save(&entries)?;
+        return Err(ScanError::RegistryAccessDenied(e));
+    }
+    Ok(change)
+}
+
+/// Restore every journaled value, newest first, and empty the journal.
+/// Returns the entries that were reverted. If a restore fails, the entries
+/// not yet reverted stay in the journal for a later attempt.
+pub fn revert_all(
+    reg: &mut dyn RegistryWriter,
+    journal: &Journal,
+) -> Result<Vec<JournalEntry>, ScanError> {
+    let mut entries = journal.entries()?;
+    let mut reverted = Vec::new();
+    while let Some(entry) = entries.pop() {
+        let outcome = match &entry.previous {
+            Some(value) => reg.write_value(entry.hive, &entry.path, &entry.name, value),
+            None => reg.delete_value(entry.hive, &entry.path, &entry.name),
+        };
+        if let Err(e) = outcome {
+            entries.push(entry);
+            journal.save(&entries)?;
+            return Err(ScanError::RegistryAccessDenied(e));
+        }
+        reverted.push(entry);
+    }
+    journal.save(&entries)?;
+    Ok(reverted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::fixtures::{context, office_root, temp_dir};
+    use crate::checks::host::FakeHost;
+    use crate::checks::registry::{FakeRegistry, RegistryReader};
+
+    const SAVE_KEY: &str = "Software\\Microsoft\\Office\\16.0\\OneNote\\Options\\Save";
+    const OPTIONS_KEY: &str = "Software\\Microsoft\\Office\\16.0\\OneNote\\Options";
+
+    /// OneNote 2016 with auto-sync switched off and full download not set.
+    fn registry() -> FakeRegistry {
+        office_root(FakeRegistry::new(), "16.0", "OneNote").with_value(
+            Hive::CurrentUser,
+            SAVE_KEY,
+            "SyncStateOffline",
+            RegValue::Dword(1),
+        )
+    }
+
+    /// Run `fix_check` for `id` with OneNote installed and working.
+    fn fix(
+        reg: &mut FakeRegistry,
+        journal: &Journal,
+        id: &str,
+        dry_run: bool,
+    ) -> Result<FixChange, ScanError> {
+        let host = FakeHost::new().with_com("OneNote.Application", Ok(()));
+        let ctx = Arc::new(context(reg.clone(), host));
+        fix_check(&CheckRegistry::builtin(), &ctx, reg, journal, id, dry_run)
+    }
+
+    fn value(reg: &FakeRegistry, path: &str, name: &str) -> Option<RegValue> {
+        reg.read_value(Hive::CurrentUser, path, name)
+    }
+
+    #[test]
+    fn fixes_are_journaled_and_reverted_newest_first() {
+        let journal = Journal::in_dir(&temp_dir("fix-journal"));
+        let mut reg = registry();
+
+        let auto = fix(&mut reg, &journal, "sync_auto", false).unwrap();
+        assert_eq!(auto.previous, Some(RegValue::Dword(1)));
+        let download = fix(&mut reg, &journal, "sync_download", false).unwrap();
+        assert_eq!(download.previous, None);
+        assert_eq!(value(&reg, OPTIONS_KEY, "DeferFdoDownload"), Some(RegValue::Dword(0)));
+
+        let entries = journal.entries().unwrap();
+        let ids: Vec<_> = entries.iter().map(|e| e.check_id.as_str()).collect();
+        assert_eq!(ids, ["sync_auto", "sync_download"]);
+
+        let reverted = revert_all(&mut reg, &journal).unwrap();
+        let ids: Vec<_> = reverted.iter().map(|e| e.check_id.as_str()).collect();
+        assert_eq!(ids, ["sync_download", "sync_auto"]);
+        assert_eq!(value(&reg, SAVE_KEY, "SyncStateOffline"), Some(RegValue::Dword(1)));
+        assert_eq!(value(&reg, OPTIONS_KEY, "DeferFdoDownload"), None);
+        assert!(journal.entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn reverting_two_writes_to_one_value_restores_the_oldest_state() {
+        let journal = Journal::in_dir(&temp_dir("fix-same-value"));
+        let mut reg = FakeRegistry::new();
+        let write = |value| RegistryFix {
+            hive: Hive::CurrentUser,
+            path: OPTIONS_KEY.into(),
+            name: "DeferFdoDownload".into(),
+            value: RegValue::Dword(value),
+            description: String::new(),
+        };
+        apply("sync_download", write(1), &mut reg, &journal, false).unwrap();
+        apply("sync_download", write(0), &mut reg, &journal, false).unwrap();
+
+        revert_all(&mut reg, &journal).unwrap();
+        assert_eq!(value(&reg, OPTIONS_KEY, "DeferFdoDownload"), None);
+    }
+
+    #[test]
+    fn passing_check_is_left_alone() {
+        let journal = Journal::in_dir(&temp_dir("fix-passing"));
+        let mut reg = registry();
+        reg.insert_value(Hive::CurrentUser, SAVE_KEY, "SyncStateOffline", RegValue::Dword(0));
+
+        let e = fix(&mut reg, &journal, "sync_auto", false).unwrap_err();
+        assert!(e.to_string().contains("nothing to fix"), "{}", e);
+        assert!(journal.entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn skipped_check_is_left_alone() {
+        let journal = Journal::in_dir(&temp_dir("fix-skipped"));
+        let mut reg = registry();
+        let ctx = Arc::new(context(reg.clone(), FakeHost::new()));
+
+        let outcome = fix_check(&CheckRegistry::builtin(), &ctx, &mut reg, &journal, "sync_auto", false);
+        assert!(outcome.is_err());
+        assert_eq!(value(&reg, SAVE_KEY, "SyncStateOffline"), Some(RegValue::Dword(1)));
+        assert!(journal.entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn dry_run_writes_nothing() {
+        let journal = Journal::in_dir(&temp_dir("fix-dry-run"));
+        let mut reg = registry();
+
+        let change = fix(&mut reg, &journal, "sync_auto", true).unwrap();
+        assert_eq!(change.fix.value, RegValue::Dword(0));
+        assert_eq!(value(&reg, SAVE_KEY, "SyncStateOffline"), Some(RegValue::Dword(1)));
+        assert!(journal.entries().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,325 @@
+//! The notebooks OneNote has open, as reported by `IApplication::GetHierarchy`.
+//! Parsing is kept apart from the COM call so fixture XML can be read on any
+//! platform.
+
+use roxmltree::{Document, Node};
+use serde::{Deserialize, Serialize};
+
+/// Everything a migration would export, down to page level.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Inventory {
+    pub notebooks: Vec<Notebook>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notebook {
+    pub id: String,
+    pub name: String,
+    /// Folder or URL the notebook is stored at.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    #[serde(default)]
+    pub section_groups: Vec<SectionGroup>,
+    #[serde(default)]
+    pub sections: Vec<Section>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SectionGroup {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    #[serde(default)]
+    pub section_groups: Vec<SectionGroup>,
+    #[serde(default)]
+    pub sections: Vec<Section>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Section {
+    pub id: String,
+    pub name: String,
+    /// The `.one` file holding the section.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    /// Password-protected and locked right now; OneNote lists no pages.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_locked: bool,
+    /// Password-protected, even if unlocked for the moment.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub encrypted: bool,
+    /// OneNote cannot write to it, e.g. on a share opened for viewing only.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+    #[serde(default)]
+    pub pages: Vec<Page>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+impl Inventory {
+    /// Every section with the notebook it belongs to, including sections
+    /// nested in section groups.
+    pub fn sections(&self) -> Vec<(&Notebook, &Section)> {
+        let mut found = Vec::new();
+        for notebook in &self.notebooks {
+            collect_sections(notebook, &notebook.sections, &notebook.section_groups, &mut found);
+        }
+        found
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.sections().iter().map(|(_, section)| section.pages.len()).sum()
+    }
+}
+
+fn collect_sections<'a>(
+    notebook: &'a Notebook,
+    sections: &'a [Section],
+    groups: &'a [SectionGroup],
+    found: &mut Vec<(&'a Notebook, &'a Section)>,
+) {
+    found.extend(sections.iter().map(|section| (notebook, section)));
+    for group in groups {
+        collect_sections(notebook, &group.sections, &group.section_groups, found);
+    }
+}
+
+/// Read the XML of a `GetHierarchy` call with page scope. The recycle bin and
+/// deleted pages are left out, as they are not migrated.
+pub fn parse_hierarchy(xml: &str) -> Result<Inventory, String> {
+    let doc = Document::parse(xml).map_err(|e| format!("Failed to parse OneNote hierarchy: {}", e))?;
+    let root = doc.root_element();
+    if root.tag_name().name() != "Notebooks" {
+        return Err(format!(
+            "Failed to parse OneNote hierarchy: expected Notebooks, found {}",
+            root.tag_name().name()
+        ));
+    }
+    let notebooks = elements(root, "Notebook")
+        .map(|node| {
+            let (section_groups, sections) = parse_children(node)?;
+            Ok(Notebook {
+                id: required(node, "ID")?,
+                name: required(node, "name")?,
+                path: required(node, "path")?,
+                last_modified: optional(node, "lastModifiedTime"),
+                section_groups,
+                sections,
+            })
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(Inventory { notebooks })
+}
+
+/// Section groups and sections directly under a notebook or section group.
+fn parse_children(parent: Node) -> Result<(Vec<SectionGroup>, Vec<Section>), String> {
+    let groups = elements(parent, "SectionGroup")
+        .filter(|node| !flag(*node, "isRecycleBin"))
+        .map(|node| {
+            let (section_groups, sections) = parse_children(node)?;
+            Ok(SectionGroup {
+                id: required(node, "ID")?,
+                name: required(node, "name")?,
+                path: required(node, "path")?,
+                last_modified: optional(node, "lastModifiedTime"),
+                section_groups,
+                sections,
+            })
+        })
+        .collect::<Result<_, String>>()?;
+    let sections = elements(parent, "Section")
+        .filter(|node| !flag(*node, "isInRecycleBin") && !flag(*node, "isDeletedPages"))
+        .map(parse_section)
+        .collect::<Result<_, String>>()?;
+    Ok((groups, sections))
+}
+
+fn parse_section(node: Node) -> Result<Section, String> {
+    let pages = elements(node, "Page")
+        .filter(|page| !flag(*page, "isInRecycleBin"))
+        .map(|page| {
+            Ok(Page {
+                id: required(page, "ID")?,
+                name: optional(page, "name").unwrap_or_default(),
+                last_modified: optional(page, "lastModifiedTime"),
+            })
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(Section {
+        id: required(node, "ID")?,
+        name: required(node, "name")?,
+        path: required(node, "path")?,
+        last_modified: optional(node, "lastModifiedTime"),
+        is_locked: flag(node, "isLocked"),
+        encrypted: flag(node, "encrypted"),
+        read_only: flag(node, "readOnly"),
+        pages,
+    })
+}
+
+/// Child elements of `parent` named `name`, in whichever OneNote schema
+/// namespace the document uses.
+fn elements<'a, 'input>(parent: Node<'a, 'input>, name: &'static str) -> impl Iterator<Item = Node<'a, 'input>> {
+    parent
+        .children()
+        .filter(move |node| node.is_element() && node.tag_name().name() == name)
+}
+
+fn required(node: Node, attribute: &str) -> Result<String, String> {
+    node.attribute(attribute).map(str::to_string).ok_or_else(|| {
+        format!(
+            "Failed to parse OneNote hierarchy: {} without {} at offset {}",
+            node.tag_name().name(),
+            attribute,
+            node.range().start
+        )
+    })
+}
+
+fn optional(node: Node, attribute: &str) -> Option<String> {
+    node.attribute(attribute).map(str::to_string)
+}
+
+fn flag(node: Node, attribute: &str) -> bool {
+    node.attribute(attribute) == Some("true")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::fixtures::HIERARCHY;
+
+    fn sample() -> Inventory {
+        parse_hierarchy(HIERARCHY).unwrap()
+    }
+
+    fn section<'a>(inventory: &'a Inventory, name: &str) -> &'a Section {
+        inventory.sections().into_iter().map(|(_, s)| s).find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn reads_notebooks_and_nested_section_groups() {
+        let inventory = sample();
+        let names: Vec<_> = inventory.notebooks.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, ["Work", "Personal"]);
+
+        let work = &inventory.notebooks[0];
+        assert_eq!(work.sections.len(), 2);
+        assert_eq!(work.section_groups.len(), 1);
+        let projects = &work.section_groups[0];
+        assert_eq!(projects.name, "Projects");
+        assert_eq!(projects.section_groups[0].name, "Archive");
+        assert_eq!(projects.section_groups[0].sections[0].name, "2022");
+
+        let sections: Vec<_> = inventory
+            .sections()
+            .into_iter()
+            .map(|(notebook, s)| (notebook.name.as_str(), s.name.as_str()))
+            .collect();
+        assert_eq!(
+            sections,
+            [
+                ("Work", "Meetings"),
+                ("Work", "HR"),
+                ("Work", "Roadmap"),
+                ("Work", "2022"),
+                ("Personal", "Recipes"),
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_out_the_recycle_bin() {
+        let inventory = sample();
+        assert!(inventory.notebooks[0].section_groups.iter().all(|g| g.name != "OneNote_RecycleBin"));
+        assert!(inventory.sections().iter().all(|(_, s)| s.name != "Deleted Pages"));
+
+        let meetings = section(&inventory, "Meetings");
+        let pages: Vec<_> = meetings.pages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(pages, ["Weekly sync", ""]);
+        assert_eq!(inventory.page_count(), 5);
+    }
+
+    #[test]
+    fn optional_attributes_may_be_missing() {
+        let inventory = sample();
+        assert_eq!(inventory.notebooks[1].last_modified, None);
+        assert_eq!(inventory.notebooks[1].path, "C:\\Users\\jdoe\\Documents\\OneNote Notebooks\\Personal\\");
+        assert_eq!(inventory.notebooks[0].section_groups[0].section_groups[0].last_modified, None);
+        assert_eq!(section(&inventory, "2022").last_modified, None);
+        assert_eq!(section(&inventory, "Meetings").pages[1].last_modified, None);
+        assert_eq!(
+            section(&inventory, "Meetings").last_modified.as_deref(),
+            Some("2024-05-02T08:14:31.000Z")
+        );
+
+        let xml = r#"<Notebooks><Notebook ID="n" name="Notes" path="C:\Notes\"><Section ID="s" name="Inbox" path="C:\Notes\Inbox.one"><Page ID="p"/></Section></Notebook></Notebooks>"#;
+        let inventory = parse_hierarchy(xml).unwrap();
+        assert_eq!(section(&inventory, "Inbox").pages[0].name, "");
+    }
+
+    #[test]
+    fn reads_protection_flags() {
+        let inventory = sample();
+        let flags = |name| {
+            let s = section(&inventory, name);
+            (s.encrypted, s.is_locked, s.read_only)
+        };
+        assert_eq!(flags("Meetings"), (false, false, false));
+        assert_eq!(flags("HR"), (true, true, false));
+        assert_eq!(flags("Roadmap"), (true, false, false));
+        assert_eq!(flags("2022"), (false, false, true));
+    }
+
+    #[test]
+    fn missing_path_is_an_error() {
+        let xml = r#"<Notebooks><Notebook ID="n" name="Notes"/></Notebooks>"#;
+        let e = parse_hierarchy(xml).unwrap_err();
+        assert!(e.contains("Notebook without path"), "{}", e);
+
+        let xml = r#"<Notebooks><Notebook ID="n" name="Notes" path="C:\Notes\"><Section ID="s" name="Inbox"/></Notebook></Notebooks>"#;
+        let e = parse_hierarchy(xml).unwrap_err();
+        assert!(e.contains("Section without path"), "{}", e);
+    }
+
+    #[test]
+    fn malformed_xml_is_an_error() {
+        for xml in [
+            "",
+            "<Notebooks>",
+            "<Notebooks><Notebook ID=\"n\" name=\"Notes\" path=\"x\"></Notebooks>",
+            "not xml at all",
+            &HIERARCHY[..HIERARCHY.len() / 2],
+        ] {
+            let e = parse_hierarchy(xml).unwrap_err();
+            assert!(e.starts_with("Failed to parse OneNote hierarchy"), "{}", e);
+        }
+
+        let e = parse_hierarchy("<Sections/>").unwrap_err();
+        assert!(e.contains("expected Notebooks, found Sections"), "{}", e);
+    }
+
+    #[test]
+    fn empty_hierarchy_has_no_notebooks() {
+        let xml = r#"<one:Notebooks xmlns:one="http://schemas.microsoft.com/office/onenote/2013/onenote"/>"#;
+        let inventory = parse_hierarchy(xml).unwrap();
+        assert!(inventory.notebooks.is_empty());
+        assert_eq!(inventory.page_count(), 0);
+    }
+}
@@ -0,0 +1,358 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{CheckStatus, ScanError, ScanResult};
+
+const HISTORY_DIR_NAME: &str = "history";
+
+/// Scans kept before the oldest are pruned.
+pub const MAX_SCANS: usize = 100;
+
+/// Past scan results, one JSON file per scan in the app data directory. The
+/// file stem is the scan's id; ids sort chronologically.
+pub struct History {
+    dir: PathBuf,
+}
+
+/// What `list_scans` shows of a stored scan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanSummary {
+    pub id: String,
+    pub timestamp: String,
+    pub overall: CheckStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<u8>,
+    /// Checks that failed, timed out or warned.
+    pub issues: usize,
+}
+
+impl ScanSummary {
+    fn new(id: String, result: &ScanResult) -> Self {
+        Self {
+            id,
+            timestamp: result.timestamp.clone(),
+            overall: result.overall.clone(),
+            score: result.score,
+            issues: result
+                .checks
+                .iter()
+                .filter(|c| !matches!(c.status, CheckStatus::Pass | CheckStatus::Skipped))
+                .count(),
+        }
+    }
+}
+
+impl History {
+    pub fn in_dir(dir: &Path) -> Self {
+        Self {
+            dir: dir.join(HISTORY_DIR_NAME),
+        }
+    }
+
+    /// Store `result` and prune the oldest scans beyond [`MAX_SCANS`].
+    /// Returns the new scan's id.
+    pub fn record(&self, result: &ScanResult) -> Result<String, ScanError> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| {
+            ScanError::Unexpected(format!("Failed to create {}: {}", self.dir.display(), e))
+        })?;
+        let stem = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+        let mut id = stem.clone();
+        let mut n = 1;
+        while self.path(&id).exists() {
+            n += 1;
+            id = format!("{}-{}", stem, n);
+        }
+        let json = serde_json::to_string_pretty(result)
+            .map_err(|e| ScanError::Unexpected(format!("Failed to serialize scan: {}", e)))?;
+        std::fs::write(self.path(&id), json)
+            .map_err(|e| ScanError::Unexpected(format!("Failed to write scan history: {}", e)))?;
+
+        let ids = self.ids()?;
+        for old in &ids[..ids.len().saturating_sub(MAX_SCANS)] {
+            let _ = std::fs::remove_file(self.path(old));
+        }
+        Ok(id)
+    }
+
+    pub fn get(&self, id: &str) -> Result<ScanResult, ScanError> {
+        let path = self.path(id);
+        if !is_valid_id(id) || !path.exists() {
+            return Err(ScanError::Unexpected(format!("Scan '{}' not found in history", id)));
+        }
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| ScanError::Unexpected(format!("Failed to read scan '{}': {}", id, e)))?;
+        serde_json::from_str(&json)
+            .map_err(|e| ScanError::Unexpected(format!("Invalid scan '{}': {}", id, e)))
+    }
+
+    /// Stored scans, newest first. Files that no longer parse are left out.
+    pub fn list(&self) -> Result<Vec<ScanSummary>, ScanError> {
+        let mut summaries: Vec<ScanSummary> = self
+            .ids()?
+            .into_iter()
+            .filter_map(|id| {
+                let result = self.get(&id).ok()?;
+                Some(ScanSummary::new(id, &result))
+            })
+            .collect();
+        summaries.reverse();
+        Ok(summaries)
+    }
+
+    /// Ids of the stored scans, oldest first.
+    fn ids(&self) -> Result<Vec<String>, ScanError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(ScanError::Unexpected(format!("Failed to read scan history: {}", e)))
+            }
+        };
+        let mut ids: Vec<String> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "json" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_string_lossy().into_owned())
+            })
+            .filter(|id| is_valid_id(id))
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Compare stored scans `from` and `to`.
+    pub fn diff(&self, from: &str, to: &str) -> Result<ScanDiff, ScanError> {
+        let before = self.get(from)?;
+        let after = self.get(to)?;
+        Ok(ScanDiff {
+            changes: diff_checks(&before, &after),
+            from: ScanSummary::new(from.into(), &before),
+            to: ScanSummary::new(to.into(), &after),
+        })
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+}
+
+/// Ids come from the frontend, so keep them to the characters `record` uses
+/// rather than joining arbitrary paths.
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.contains("..")
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+}
+
+/// How a check differs between two scans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    /// Only in the newer scan, e.g. a policy rule that was added.
+    Added,
+    /// Only in the older scan.
+    Removed,
+    /// Now passes, and did not before.
+    Resolved,
+    /// Passed before, and no longer does.
+    Regressed,
+    /// A different status, neither of them a pass.
+    StatusChanged,
+    /// Same status, different message, e.g. a newer version was found.
+    MessageChanged,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckChange {
+    pub id: String,
+    pub label: String,
+    pub kind: ChangeKind,
+    /// Status and message in the older scan; `None` if the check did not run.
+    pub before: Option<CheckState>,
+    pub after: Option<CheckState>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckState {
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+/// Checks that changed status or message between scan `from` and the later
+/// scan `to`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanDiff {
+    pub from: ScanSummary,
+    pub to: ScanSummary,
+    pub changes: Vec<CheckChange>,
+}
+
+/// Changes from `before` to `after`, in the order of `after`, followed by
+/// checks that were removed. Messages are only compared between scans in the
+/// same language.
+pub fn diff_checks(before: &ScanResult, after: &ScanResult) -> Vec<CheckChange> {
+    let same_locale = before.locale == after.locale;
+    let state = |result: &ScanResult, id: &str| {
+        result.checks.iter().find(|c| c.id == id).map(|c| CheckState {
+            status: c.status.clone(),
+            message: c.message.clone(),
+        })
+    };
+
+    let mut changes = Vec::new();
+    for check in &after.checks {
+        let then = state(before, &check.id);
+        let kind = match &then {
+            None => ChangeKind::Added,
+            Some(then) if then.status == check.status => {
+                if then.message == check.message || !same_locale {
+                    continue;
+                }
+                ChangeKind::MessageChanged
+            }
+            Some(_) if check.status == CheckStatus::Pass => ChangeKind::Resolved,
+            Some(then) if then.status == CheckStatus::Pass => ChangeKind::Regressed,
+            Some(_) => ChangeKind::StatusChanged,
+        };
+        changes.push(CheckChange {
+            id: check.id.clone(),
+            label: check.label.clone(),
+            kind,
+            before: then,
+            after: state(after, &check.id),
+        });
+    }
+    for check in &before.checks {
+        if state(after, &check.id).is_none() {
+            changes.push(CheckChange {
+                id: check.id.clone(),
+                label: check.label.clone(),
+                kind: ChangeKind::Removed,
+                before: state(before, &check.id),
+                after: None,
+            });
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::fixtures::temp_dir;
+    use crate::i18n::Locale;
+    use crate::types::CheckResult;
+
+    fn check(id: &str, status: CheckStatus, message: &str) -> CheckResult {
+        CheckResult {
+            id: id.into(),
+            label: id.into(),
+            status,
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    fn scan(checks: Vec<CheckResult>) -> ScanResult {
+        ScanResult {
+            checks,
+            timestamp: "2024-05-02T08:14:31+00:00".into(),
+            duration_ms: 2400,
+            os_info: String::new(),
+            overall: CheckStatus::Pass,
+            score: Some(100),
+            locale: Locale::En,
+            redacted: false,
+            inventory: None,
+        }
+    }
+
+    #[test]
+    fn ids_cannot_leave_the_history_folder() {
+        assert!(is_valid_id("20240502T081431.123Z"));
+        assert!(is_valid_id("20240502T081431.123Z-2"));
+        let rejected = ["", "..", "../secrets", "..\\secrets", "a/b", "a\\b", "C:evil", "x..y", "id json"];
+        for id in rejected {
+            assert!(!is_valid_id(id), "{}", id);
+        }
+
+        let dir = temp_dir("history-traversal");
+        std::fs::write(dir.join("outside.json"), "{}").unwrap();
+        let history = History::in_dir(&dir);
+        let e = history.get("../outside").unwrap_err();
+        assert!(e.to_string().contains("not found"), "{}", e);
+    }
+
+    #[test]
+    fn record_prunes_the_oldest_scans() {
+        let dir = temp_dir("history-prune");
+        let history = History::in_dir(&dir);
+        let json = serde_json::to_string(&scan(Vec::new())).unwrap();
+        std::fs::create_dir_all(&history.dir).unwrap();
+        for n in 0..MAX_SCANS {
+            std::fs::write(history.path(&format!("20200101T000000.{:03}Z", n)), &json).unwrap();
+        }
+        std::fs::write(history.dir.join("notes.txt"), "kept").unwrap();
+
+        let id = history.record(&scan(Vec::new())).unwrap();
+        let ids = history.ids().unwrap();
+        assert_eq!(ids.len(), MAX_SCANS);
+        assert_eq!(ids[0], "20200101T000000.001Z");
+        assert_eq!(ids.last(), Some(&id));
+        assert!(history.dir.join("notes.txt").exists());
+
+        let listed = history.list().unwrap();
+        assert_eq!(listed[0].id, id);
+    }
+
+    #[test]
+    fn diff_classifies_changes() {
+        let before = scan(vec![
+            check("onenote", CheckStatus::Fail, "not found"),
+            check("word", CheckStatus::Pass, "found"),
+            check("sync_auto", CheckStatus::Warning, "off"),
+            check("joplin", CheckStatus::Pass, "3.0.12"),
+            check("disk", CheckStatus::Pass, "plenty"),
+            check("legacy_rule", CheckStatus::Pass, "ok"),
+        ]);
+        let after = scan(vec![
+            check("onenote", CheckStatus::Pass, "found"),
+            check("word", CheckStatus::Warning, "not registered"),
+            check("sync_auto", CheckStatus::Fail, "off"),
+            check("joplin", CheckStatus::Pass, "3.1.4"),
+            check("disk", CheckStatus::Pass, "plenty"),
+            check("new_rule", CheckStatus::Fail, "missing"),
+        ]);
+
+        let kinds: Vec<_> = diff_checks(&before, &after)
+            .into_iter()
+            .map(|c| (c.id, c.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("onenote".to_string(), ChangeKind::Resolved),
+                ("word".to_string(), ChangeKind::Regressed),
+                ("sync_auto".to_string(), ChangeKind::StatusChanged),
+                ("joplin".to_string(), ChangeKind::MessageChanged),
+                ("new_rule".to_string(), ChangeKind::Added),
+                ("legacy_rule".to_string(), ChangeKind::Removed),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_ignores_messages_in_another_language() {
+        let before = scan(vec![check("joplin", CheckStatus::Pass, "3.0.12 found")]);
+        let mut after = scan(vec![check("joplin", CheckStatus::Pass, "3.0.12 gevonden")]);
+        after.locale = Locale::Nl;
+        assert!(diff_checks(&before, &after).is_empty());
+    }
+}
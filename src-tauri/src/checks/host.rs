@@ -0,0 +1,276 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Evidence;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PathKind {
+    File,
+    Dir,
+}
+
+/// Space on the volume holding a path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VolumeSpace {
+    /// Root of the volume, e.g. `C:\`, to tell whether two paths share it.
+    pub root: String,
+    /// Bytes the current user may still write.
+    pub free: u64,
+    pub total: u64,
+}
+
+/// Everything besides the registry that checks read from the machine:
+/// environment variables, file probes, disk space and COM automation.
+pub trait HostProbe: Send + Sync {
+    fn env_var(&self, name: &str) -> Option<String>;
+
+    /// What exists at `path`, or `None` if nothing does.
+    fn path_kind(&self, path: &Path) -> Option<PathKind>;
+
+    /// Combined size of the files under the folder `path`, or `None` if
+    /// there is no such folder.
+    fn dir_size(&self, path: &Path) -> Option<u64>;
+
+    /// The volume `path` is on, or would be on once created.
+    fn volume_space(&self, path: &Path) -> Option<VolumeSpace>;
+
+    /// Try to activate the COM server registered for `prog_id`.
+    fn com_activation(&self, prog_id: &str) -> Result<(), String>;
+
+    /// Hierarchy XML of the notebooks OneNote has open, down to page level.
+    fn onenote_hierarchy(&self) -> Result<String, String>;
+}
+
+/// Evidence for the outcome of [`HostProbe::com_activation`].
+pub fn com_evidence(prog_id: &str, outcome: &Result<(), String>) -> Evidence {
+    let value = match outcome {
+        Ok(()) => "activated".to_string(),
+        Err(e) => e.clone(),
+    };
+    Evidence::new(prog_id, value, "COM")
+}
+
+/// Evidence that `path` was found on disk.
+pub fn path_evidence(key: &str, path: &Path) -> Evidence {
+    Evidence::new(key, path.to_string_lossy(), "File system")
+}
+
+/// `text` with `%NAME%` references replaced by environment variables.
+/// Unknown names are left as they are.
+pub fn expand_env(host: &dyn HostProbe, text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('%') {
+        let Some(len) = rest[start + 1..].find('%') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + len];
+        match host.env_var(name).filter(|_| !name.is_empty()) {
+            Some(value) => {
+                out.push_str(&rest[..start]);
+                out.push_str(&value);
+                rest = &rest[start + len + 2..];
+            }
+            None => {
+                // Keep the first `%`; the second may open the next reference.
+                out.push_str(&rest[..=start]);
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Probes the machine the tool is running on.
+pub struct LiveHost;
+
+impl HostProbe for LiveHost {
+    fn env_var(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+
+    fn path_kind(&self, path: &Path) -> Option<PathKind> {
+        let meta = std::fs::metadata(path).ok()?;
+        Some(if meta.is_dir() { PathKind::Dir } else { PathKind::File })
+    }
+
+    fn dir_size(&self, path: &Path) -> Option<u64> {
+        std::fs::metadata(path).ok()?.is_dir().then(|| tree_size(path))
+    }
+
+    fn volume_space(&self, path: &Path) -> Option<VolumeSpace> {
+        live_volume_space(path)
+    }
+
+    fn com_activation(&self, prog_id: &str) -> Result<(), String> {
+        super::registry::test_com_activation(prog_id)
+    }
+
+    fn onenote_hierarchy(&self) -> Result<String, String> {
+        super::registry::read_onenote_hierarchy()
+    }
+}
+
+/// Bytes in the files under `dir`. Links are not followed, so a junction
+/// back into the tree is not counted twice; unreadable entries count as empty.
+fn tree_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match std::fs::symlink_metadata(entry.path()) {
+            Ok(meta) if meta.is_dir() => tree_size(&entry.path()),
+            Ok(meta) if meta.is_file() => meta.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(windows)]
+fn live_volume_space(path: &Path) -> Option<VolumeSpace> {
+    use windows::core::HSTRING;
+    use windows::Win32::Storage::FileSystem::{GetDiskFreeSpaceExW, GetVolumePathNameW};
+
+    // Resolves paths that do not exist yet, such as a new export folder.
+    let mut buffer = [0u16; 261];
+    unsafe { GetVolumePathNameW(&HSTRING::from(path), &mut buffer) }.ok()?;
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    let root = String::from_utf16_lossy(&buffer[..len]);
+
+    let (mut free, mut total) = (0u64, 0u64);
+    unsafe {
+        GetDiskFreeSpaceExW(
+            &HSTRING::from(root.as_str()),
+            Some(&mut free as *mut u64),
+            Some(&mut total as *mut u64),
+            None,
+        )
+    }
+    .ok()?;
+    Some(VolumeSpace { root, free, total })
+}
+
+#[cfg(not(windows))]
+fn live_volume_space(_path: &Path) -> Option<VolumeSpace> {
+    None
+}
+
+/// In-memory host used for fixtures and snapshot replay. Environment variable
+/// names are matched case-insensitively, like on Windows.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FakeHost {
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    #[serde(default)]
+    paths: BTreeMap<String, PathKind>,
+    #[serde(default)]
+    com: BTreeMap<String, Result<(), String>>,
+    #[serde(default)]
+    dir_sizes: BTreeMap<String, u64>,
+    #[serde(default)]
+    volumes: BTreeMap<String, VolumeSpace>,
+    #[serde(default)]
+    hierarchy: Option<Result<String, String>>,
+}
+
+impl FakeHost {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert_env(&mut self, name: &str, value: &str) {
+        self.env.insert(name.to_uppercase(), value.to_string());
+    }
+
+    pub fn insert_path(&mut self, path: &Path, kind: PathKind) {
+        self.paths.insert(path.to_string_lossy().to_string(), kind);
+    }
+
+    pub fn insert_com(&mut self, prog_id: &str, outcome: Result<(), String>) {
+        self.com.insert(prog_id.to_string(), outcome);
+    }
+
+    pub fn insert_dir_size(&mut self, path: &Path, size: u64) {
+        self.dir_sizes.insert(path.to_string_lossy().to_string(), size);
+    }
+
+    pub fn insert_volume(&mut self, path: &Path, volume: VolumeSpace) {
+        self.volumes.insert(path.to_string_lossy().to_string(), volume);
+    }
+
+    pub fn set_hierarchy(&mut self, outcome: Result<String, String>) {
+        self.hierarchy = Some(outcome);
+    }
+
+    #[cfg(test)]
+    pub fn with_env(mut self, name: &str, value: &str) -> Self {
+        self.insert_env(name, value);
+        self
+    }
+
+    #[cfg(test)]
+    pub fn with_path(mut self, path: impl AsRef<Path>, kind: PathKind) -> Self {
+        self.insert_path(path.as_ref(), kind);
+        self
+    }
+
+    #[cfg(test)]
+    pub fn with_com(mut self, prog_id: &str, outcome: Result<(), String>) -> Self {
+        self.insert_com(prog_id, outcome);
+        self
+    }
+
+    #[cfg(test)]
+    pub fn with_dir_size(mut self, path: impl AsRef<Path>, size: u64) -> Self {
+        self.insert_dir_size(path.as_ref(), size);
+        self
+    }
+
+    #[cfg(test)]
+    pub fn with_volume(mut self, path: impl AsRef<Path>, volume: VolumeSpace) -> Self {
+        self.insert_volume(path.as_ref(), volume);
+        self
+    }
+
+    #[cfg(test)]
+    pub fn with_hierarchy(mut self, outcome: Result<String, String>) -> Self {
+        self.set_hierarchy(outcome);
+        self
+    }
+}
+
+impl HostProbe for FakeHost {
+    fn env_var(&self, name: &str) -> Option<String> {
+        self.env.get(&name.to_uppercase()).cloned()
+    }
+
+    fn path_kind(&self, path: &Path) -> Option<PathKind> {
+        self.paths.get(path.to_string_lossy().as_ref()).copied()
+    }
+
+    fn dir_size(&self, path: &Path) -> Option<u64> {
+        self.dir_sizes.get(path.to_string_lossy().as_ref()).copied()
+    }
+
+    fn volume_space(&self, path: &Path) -> Option<VolumeSpace> {
+        self.volumes.get(path.to_string_lossy().as_ref()).cloned()
+    }
+
+    fn com_activation(&self, prog_id: &str) -> Result<(), String> {
+        self.com
+            .get(prog_id)
+            .cloned()
+            .unwrap_or_else(|| Err(format!("No COM result recorded for {}", prog_id)))
+    }
+
+    fn onenote_hierarchy(&self) -> Result<String, String> {
+        self.hierarchy
+            .clone()
+            .unwrap_or_else(|| Err("No OneNote hierarchy recorded".into()))
+    }
+}
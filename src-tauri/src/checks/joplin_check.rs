@@ -1,43 +1,78 @@
-use crate::types::{CheckResult, CheckStatus};
+use std::path::{Path, PathBuf};
 
-#[cfg(windows)]
-pub fn check() -> CheckResult {
-    // Strategy 1: Registry Uninstall keys (most reliable for installed copies)
-    if let Some((version, location)) = find_joplin_in_registry() {
-        return CheckResult {
-            id: "joplin".into(),
-            label: "Joplin".into(),
-            status: CheckStatus::Pass,
-            message: format!("{} found at {}", version, location),
-            remediation: None,
-        };
+use crate::types::{CheckCategory, CheckResult, CheckStatus, Evidence, RemediationStep, Severity};
+
+use super::host::{self, HostProbe, PathKind};
+use super::registry::{Hive, RegistryReader};
+use super::{Check, ScanContext};
+
+pub struct JoplinCheck;
+
+impl Check for JoplinCheck {
+    fn id(&self) -> &'static str {
+        "joplin"
     }
 
-    // Strategy 2: Common installation paths on disk
-    if let Some(path) = find_joplin_on_disk() {
-        return CheckResult {
-            id: "joplin".into(),
-            label: "Joplin".into(),
-            status: CheckStatus::Pass,
-            message: format!("Found at {}", path),
-            remediation: None,
-        };
+    fn label(&self) -> &'static str {
+        "Joplin Desktop"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Joplin
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Blocker
+    }
+
+    fn run(&self, ctx: &ScanContext) -> CheckResult {
+        if !ctx.is_supported() {
+            return unsupported(ctx);
+        }
+        check(ctx)
+    }
+}
+
+pub fn check(ctx: &ScanContext) -> CheckResult {
+    match ctx.joplin_install() {
+        Some(install @ JoplinInstall::Registered { version, location, .. }) => {
+            return CheckResult {
+                id: "joplin".into(),
+                label: "Joplin".into(),
+                status: CheckStatus::Pass,
+                message: ctx.t("joplin.found_registered", &[("version", version), ("location", location)]),
+                remediation: Vec::new(),
+                evidence: install.evidence(),
+                ..Default::default()
+            };
+        }
+        Some(install @ JoplinInstall::OnDisk { path }) => {
+            return CheckResult {
+                id: "joplin".into(),
+                label: "Joplin".into(),
+                status: CheckStatus::Pass,
+                message: ctx.t("joplin.found_on_disk", &[("path", path)]),
+                remediation: Vec::new(),
+                evidence: install.evidence(),
+                ..Default::default()
+            };
+        }
+        None => {}
     }
 
-    // Strategy 3: Profile directory exists (Joplin was used before but exe not found)
-    if joplin_profile_exists() {
+    // Fallback: profile directory exists (Joplin was used before but exe not found)
+    if let Some(profile) = joplin_profile_dir(ctx.host()) {
         return CheckResult {
             id: "joplin".into(),
             label: "Joplin".into(),
             status: CheckStatus::Warning,
-            message: "Joplin profile directory found, but application not detected.".into(),
-            remediation: Some(
-                "A Joplin configuration folder exists but the application was not found. \
-                 Joplin may have been uninstalled or is a portable installation in a \
-                 non-standard location. Reinstall from https://joplinapp.org \
-                 or verify it is accessible."
-                    .into(),
-            ),
+            message: ctx.t("joplin.profile_only", &[]),
+            remediation: vec![
+                ctx.locale().instruction("joplin.profile_only.remediation", &[]),
+                RemediationStep::link(ctx.t("joplin.download", &[]), JOPLIN_DOWNLOAD_URL),
+            ],
+            evidence: vec![host::path_evidence("Profile directory", &profile)],
+            ..Default::default()
         };
     }
 
@@ -46,180 +81,106 @@ pub fn check() -> CheckResult {
         id: "joplin".into(),
         label: "Joplin Desktop".into(),
         status: CheckStatus::Fail,
-        message: "Joplin desktop application not found.".into(),
-        remediation: Some(
-            "Install Joplin from https://joplinapp.org. The desktop application \
-             is required as the migration target. Both the per-user install and the \
-             system-wide install are supported."
-                .into(),
-        ),
+        message: ctx.t("joplin.not_found", &[]),
+        remediation: vec![
+            ctx.locale().instruction("joplin.not_found.remediation", &[]),
+            RemediationStep::link(ctx.t("joplin.download", &[]), JOPLIN_DOWNLOAD_URL),
+            RemediationStep::command(
+                "winget install --id Joplin.Joplin",
+                ctx.t("joplin.winget", &[]),
+            ),
+        ],
+        ..Default::default()
     }
 }
 
-// ---------------------------------------------------------------------------
-// Registry detection (User + System Uninstall keys)
-// ---------------------------------------------------------------------------
-
-#[cfg(windows)]
-fn find_joplin_in_registry() -> Option<(String, String)> {
-    use windows::Win32::System::Registry::*;
-
-    // User install writes to HKCU, system install writes to HKLM
-    let roots = [HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE];
-
-    for root in &roots {
-        if let Some(result) = scan_uninstall_keys(*root) {
-            return Some(result);
-        }
-    }
-    None
+/// Where Joplin Desktop was found.
+#[derive(Debug, Clone)]
+pub enum JoplinInstall {
+    /// Listed under an Uninstall key. `version` is e.g. "Joplin 3.0.15".
+    Registered {
+        version: String,
+        location: String,
+        /// The Uninstall subkey, including its hive.
+        source: String,
+    },
+    /// Found at one of the standard install paths.
+    OnDisk { path: String },
 }
 
-#[cfg(windows)]
-fn scan_uninstall_keys(
-    root: windows::Win32::System::Registry::HKEY,
-) -> Option<(String, String)> {
-    use windows::Win32::Foundation::*;
-    use windows::Win32::System::Registry::*;
-    use windows::core::*;
-
-    unsafe {
-        let subkey = w!("SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall");
-        let mut key = HKEY::default();
-        let status = RegOpenKeyExW(root, subkey, 0, KEY_READ, &mut key);
-        if status != ERROR_SUCCESS {
-            return None;
-        }
-
-        let mut index = 0u32;
-        let mut name_buf = vec![0u16; 512];
-
-        loop {
-            let mut name_len = name_buf.len() as u32;
-            let result = RegEnumKeyExW(
-                key,
-                index,
-                PWSTR::from_raw(name_buf.as_mut_ptr()),
-                &mut name_len,
-                None,
-                PWSTR::null(),
-                None,
-                None,
-            );
-
-            if result != ERROR_SUCCESS {
-                break;
+impl JoplinInstall {
+    pub fn evidence(&self) -> Vec<Evidence> {
+        match self {
+            JoplinInstall::Registered { version, location, source } => vec![
+                Evidence::new("DisplayVersion", version.as_str(), source.as_str()),
+                Evidence::new("InstallLocation", location.as_str(), source.as_str()),
+            ],
+            JoplinInstall::OnDisk { path } => {
+                vec![host::path_evidence("Joplin.exe", Path::new(path))]
             }
-
-            let subkey_name =
-                String::from_utf16_lossy(&name_buf[..name_len as usize]);
-
-            let full_path = format!(
-                "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall\\{}",
-                subkey_name
-            );
-            let full_path_wide: Vec<u16> =
-                full_path.encode_utf16().chain(std::iter::once(0)).collect();
-
-            let mut sub_key = HKEY::default();
-            let open = RegOpenKeyExW(
-                root,
-                PCWSTR::from_raw(full_path_wide.as_ptr()),
-                0,
-                KEY_READ,
-                &mut sub_key,
-            );
-
-            if open == ERROR_SUCCESS {
-                let display_name = read_reg_sz(sub_key, w!("DisplayName"));
-                let install_location =
-                    read_reg_sz(sub_key, w!("InstallLocation"));
-                let display_version =
-                    read_reg_sz(sub_key, w!("DisplayVersion"));
-                let _ = RegCloseKey(sub_key);
-
-                if let Some(ref name) = display_name {
-                    if name.to_lowercase().contains("joplin") {
-                        let version_str = display_version
-                            .map(|v| format!("Joplin {}", v))
-                            .unwrap_or_else(|| "Joplin".into());
-                        let location_str = install_location
-                            .unwrap_or_else(|| "(registry entry found)".into());
-                        let _ = RegCloseKey(key);
-                        return Some((version_str, location_str));
-                    }
-                }
-            }
-
-            index += 1;
         }
+    }
+}
 
-        let _ = RegCloseKey(key);
-        None
+/// Registry Uninstall keys first (most reliable for installed copies), then
+/// the common installation paths on disk.
+pub fn detect_joplin_install(reg: &dyn RegistryReader, host: &dyn HostProbe) -> Option<JoplinInstall> {
+    if let Some(install) = find_joplin_in_registry(reg) {
+        return Some(install);
     }
+    find_joplin_on_disk(host).map(|path| JoplinInstall::OnDisk { path })
 }
 
-#[cfg(windows)]
-unsafe fn read_reg_sz(
-    key: windows::Win32::System::Registry::HKEY,
-    value_name: windows::core::PCWSTR,
-) -> Option<String> {
-    use windows::Win32::Foundation::*;
-    use windows::Win32::System::Registry::*;
+// ---------------------------------------------------------------------------
+// Registry detection (User + System Uninstall keys)
+// ---------------------------------------------------------------------------
 
-    let mut buf_size: u32 = 0;
-    let mut value_type = REG_VALUE_TYPE::default();
+const JOPLIN_DOWNLOAD_URL: &str = "https://joplinapp.org/download/";
+const UNINSTALL_KEY: &str = "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall";
 
-    let _ = RegQueryValueExW(
-        key,
-        value_name,
-        None,
-        Some(&mut value_type),
-        None,
-        Some(&mut buf_size),
-    );
+fn find_joplin_in_registry(reg: &dyn RegistryReader) -> Option<JoplinInstall> {
+    // User install writes to HKCU, system install writes to HKLM
+    let roots = [Hive::CurrentUser, Hive::LocalMachine];
 
-    if buf_size == 0 {
-        return None;
-    }
+    roots.iter().find_map(|root| scan_uninstall_keys(reg, *root))
+}
 
-    let mut buffer = vec![0u8; buf_size as usize];
-    let status = RegQueryValueExW(
-        key,
-        value_name,
-        None,
-        Some(&mut value_type),
-        Some(buffer.as_mut_ptr()),
-        Some(&mut buf_size),
-    );
+fn scan_uninstall_keys(reg: &dyn RegistryReader, root: Hive) -> Option<JoplinInstall> {
+    for subkey_name in reg.subkeys(root, UNINSTALL_KEY) {
+        let full_path = format!("{}\\{}", UNINSTALL_KEY, subkey_name);
+        let read_sz = |name: &str| {
+            reg.read_string(root, &full_path, name)
+                .filter(|s| !s.is_empty())
+        };
 
-    if status != ERROR_SUCCESS {
-        return None;
+        let display_name = read_sz("DisplayName");
+        if let Some(ref name) = display_name {
+            if name.to_lowercase().contains("joplin") {
+                let version = read_sz("DisplayVersion")
+                    .map(|v| format!("Joplin {}", v))
+                    .unwrap_or_else(|| "Joplin".into());
+                let location = read_sz("InstallLocation")
+                    .unwrap_or_else(|| "(registry entry found)".into());
+                return Some(JoplinInstall::Registered {
+                    version,
+                    location,
+                    source: format!("{}\\{}", root.prefix(), full_path),
+                });
+            }
+        }
     }
-
-    let wide: Vec<u16> = buffer
-        .chunks_exact(2)
-        .map(|c| u16::from_le_bytes([c[0], c[1]]))
-        .collect();
-    let s = String::from_utf16_lossy(&wide)
-        .trim_end_matches('\0')
-        .to_string();
-
-    if s.is_empty() { None } else { Some(s) }
+    None
 }
 
 // ---------------------------------------------------------------------------
 // Filesystem detection (common install paths)
 // ---------------------------------------------------------------------------
 
-#[cfg(windows)]
-fn find_joplin_on_disk() -> Option<String> {
-    use std::path::PathBuf;
-
+fn find_joplin_on_disk(host: &dyn HostProbe) -> Option<String> {
     let mut candidates: Vec<PathBuf> = Vec::new();
 
     // User install (standard Joplin installer, "Install for me only")
-    if let Ok(local) = std::env::var("LOCALAPPDATA") {
+    if let Some(local) = host.env_var("LOCALAPPDATA") {
         candidates.push(
             PathBuf::from(&local)
                 .join("Programs")
@@ -229,21 +190,21 @@ fn find_joplin_on_disk() -> Option<String> {
     }
 
     // System-wide install ("Install for all users")
-    if let Ok(pf) = std::env::var("PROGRAMFILES") {
+    if let Some(pf) = host.env_var("PROGRAMFILES") {
         candidates.push(
             PathBuf::from(&pf).join("Joplin").join("Joplin.exe"),
         );
     }
 
     // 32-bit program files on 64-bit Windows
-    if let Ok(pf86) = std::env::var("PROGRAMFILES(X86)") {
+    if let Some(pf86) = host.env_var("PROGRAMFILES(X86)") {
         candidates.push(
             PathBuf::from(&pf86).join("Joplin").join("Joplin.exe"),
         );
     }
 
     for path in candidates {
-        if path.exists() {
+        if host.path_kind(&path).is_some() {
             return Some(path.to_string_lossy().to_string());
         }
     }
@@ -254,26 +215,22 @@ fn find_joplin_on_disk() -> Option<String> {
 // Profile directory fallback
 // ---------------------------------------------------------------------------
 
-#[cfg(windows)]
-fn joplin_profile_exists() -> bool {
-    if let Ok(appdata) = std::env::var("APPDATA") {
-        let profile = std::path::PathBuf::from(appdata).join("joplin-desktop");
-        return profile.is_dir();
-    }
-    false
+fn joplin_profile_dir(host: &dyn HostProbe) -> Option<PathBuf> {
+    let profile = PathBuf::from(host.env_var("APPDATA")?).join("joplin-desktop");
+    (host.path_kind(&profile) == Some(PathKind::Dir)).then_some(profile)
 }
 
 // ---------------------------------------------------------------------------
 // Non-Windows stub
 // ---------------------------------------------------------------------------
 
-#[cfg(not(windows))]
-pub fn check() -> CheckResult {
+fn unsupported(ctx: &ScanContext) -> CheckResult {
     CheckResult {
         id: "joplin".into(),
         label: "Joplin Desktop".into(),
         status: CheckStatus::Fail,
-        message: "Not running on Windows - cannot check Joplin.".into(),
-        remediation: Some("This tool must be run on Windows.".into()),
+        message: ctx.t("joplin.unsupported", &[]),
+        remediation: vec![ctx.locale().instruction("common.windows_required", &[])],
+        ..Default::default()
     }
 }
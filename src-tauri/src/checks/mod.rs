@@ -1,23 +1,374 @@
-#[allow(dead_code)]
 pub mod registry;
+pub mod host;
+pub mod snapshot;
+pub mod scheduler;
+pub mod policy;
+pub mod fix;
+pub mod history;
+pub mod hierarchy;
 pub mod joplin_check;
 pub mod os_check;
 pub mod onenote_check;
 pub mod word_check;
 pub mod sync_check;
+pub mod disk_check;
+pub mod section_check;
+pub mod rule_check;
 
-use crate::types::{CheckStatus, ScanResult, ScanError};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
-pub fn run_all_checks() -> Result<ScanResult, ScanError> {
-    let joplin = joplin_check::check();
-    let os = os_check::check();
-    let onenote = onenote_check::check();
-    let word = word_check::check();
-    let sync_auto = sync_check::check_auto_sync();
-    let sync_download = sync_check::check_full_download();
+use crate::i18n::{Locale, Params};
+use crate::types::{
+    CheckCategory, CheckInfo, CheckResult, CheckStatus, ScanError, ScanResult, Severity,
+};
+use hierarchy::Inventory;
+use host::HostProbe;
+use fix::RegistryFix;
+use joplin_check::JoplinInstall;
+use os_check::OsVersion;
+use policy::Policy;
+use registry::{ClickToRunInfo, OfficeInstall, RegistryReader};
+use scheduler::CancelToken;
 
-    let checks = vec![joplin, os, onenote, word, sync_auto, sync_download];
-    let overall = if checks.iter().any(|c| c.status == CheckStatus::Fail) {
+/// Time a single check may take before it is reported as timed out.
+pub const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Shared state handed to every check while a scan is running.
+///
+/// Facts several checks rely on (Office installs, Click-to-Run, Joplin, OS
+/// version) are detected lazily the first time they are asked for and then
+/// reused, so every check in a scan sees the same answer.
+pub struct ScanContext {
+    registry: Arc<dyn RegistryReader>,
+    host: Arc<dyn HostProbe>,
+    supported: bool,
+    timeout: Duration,
+    cancel: CancelToken,
+    policy: Arc<Policy>,
+    locale: Locale,
+    onenote_install: OnceLock<Option<OfficeInstall>>,
+    word_install: OnceLock<Option<OfficeInstall>>,
+    click_to_run: OnceLock<Option<ClickToRunInfo>>,
+    joplin_install: OnceLock<Option<JoplinInstall>>,
+    os_version: OnceLock<Result<OsVersion, String>>,
+    inventory: OnceLock<Result<Inventory, String>>,
+}
+
+impl ScanContext {
+    /// Context backed by the given probes, e.g. [`registry::FakeRegistry`] and
+    /// [`host::FakeHost`] fixtures or a replayed snapshot.
+    pub fn new(registry: Arc<dyn RegistryReader>, host: Arc<dyn HostProbe>) -> Self {
+        Self {
+            registry,
+            host,
+            supported: true,
+            timeout: DEFAULT_CHECK_TIMEOUT,
+            cancel: CancelToken::new(),
+            policy: Arc::new(Policy::default()),
+            locale: Locale::default(),
+            onenote_install: OnceLock::new(),
+            word_install: OnceLock::new(),
+            click_to_run: OnceLock::new(),
+            joplin_install: OnceLock::new(),
+            os_version: OnceLock::new(),
+            inventory: OnceLock::new(),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_cancel_token(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    pub fn with_policy(mut self, policy: Arc<Policy>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Context reading the live system. On non-Windows hosts there is nothing
+    /// to inspect, so every check reports that it cannot run.
+    #[cfg(windows)]
+    pub fn system() -> Self {
+        Self::new(Arc::new(registry::Win32Registry), Arc::new(host::LiveHost))
+    }
+
+    #[cfg(not(windows))]
+    pub fn system() -> Self {
+        Self {
+            supported: false,
+            ..Self::new(Arc::new(registry::FakeRegistry::new()), Arc::new(host::LiveHost))
+        }
+    }
+
+    pub fn registry(&self) -> &dyn RegistryReader {
+        self.registry.as_ref()
+    }
+
+    pub fn host(&self) -> &dyn HostProbe {
+        self.host.as_ref()
+    }
+
+    /// Whether the checks can inspect this system at all.
+    pub fn is_supported(&self) -> bool {
+        self.supported
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn cancel_token(&self) -> &CancelToken {
+        &self.cancel
+    }
+
+    /// Thresholds in effect for this scan.
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// Language of the messages and remediation this scan produces.
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// Shorthand for [`Locale::t`] in the scan's language.
+    pub fn t(&self, key: &str, params: Params) -> String {
+        self.locale.t(key, params)
+    }
+
+    /// Desktop OneNote install found under an Office `InstallRoot` key.
+    pub fn onenote_install(&self) -> Option<&OfficeInstall> {
+        self.onenote_install
+            .get_or_init(|| {
+                registry::detect_office_install(self.registry(), "OneNote", &self.policy.office_versions)
+            })
+            .as_ref()
+    }
+
+    /// Desktop Word install found under an Office `InstallRoot` key.
+    pub fn word_install(&self) -> Option<&OfficeInstall> {
+        self.word_install
+            .get_or_init(|| {
+                registry::detect_office_install(self.registry(), "Word", &self.policy.office_versions)
+            })
+            .as_ref()
+    }
+
+    pub fn click_to_run(&self) -> Option<&ClickToRunInfo> {
+        self.click_to_run
+            .get_or_init(|| registry::find_click_to_run(self.registry()))
+            .as_ref()
+    }
+
+    pub fn joplin_install(&self) -> Option<&JoplinInstall> {
+        self.joplin_install
+            .get_or_init(|| joplin_check::detect_joplin_install(self.registry(), self.host()))
+            .as_ref()
+    }
+
+    pub fn os_version(&self) -> Result<&OsVersion, &str> {
+        self.os_version
+            .get_or_init(|| os_check::read_os_version(self.registry()))
+            .as_ref()
+            .map_err(String::as_str)
+    }
+
+    /// Notebooks OneNote has open. OneNote is only started for this when a
+    /// check asks, and the scan result carries the inventory only then.
+    pub fn onenote_inventory(&self) -> Result<&Inventory, &str> {
+        self.inventory
+            .get_or_init(|| {
+                self.host()
+                    .onenote_hierarchy()
+                    .and_then(|xml| hierarchy::parse_hierarchy(&xml))
+            })
+            .as_ref()
+            .map_err(String::as_str)
+    }
+}
+
+/// A single readiness check. Implementations are registered in a
+/// [`CheckRegistry`] and may run concurrently with each other, except that a
+/// check always runs after the checks it depends on.
+pub trait Check: Send + Sync {
+    /// Stable identifier, also used as `CheckResult::id`.
+    fn id(&self) -> &str;
+    fn label(&self) -> &str;
+    fn category(&self) -> CheckCategory;
+    fn run(&self, ctx: &ScanContext) -> CheckResult;
+
+    /// Ids of checks that must pass (or warn) for this check to apply. If any
+    /// of them fails, this check is reported as skipped without running.
+    fn depends_on(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Major
+    }
+
+    /// Registry change that resolves a failing or warning result, applied
+    /// only when the user explicitly asks for it.
+    fn fix(&self, _ctx: &ScanContext) -> Option<RegistryFix> {
+        None
+    }
+
+    /// Share of the readiness score this check accounts for, relative to the
+    /// other checks in the scan.
+    fn weight(&self) -> u32 {
+        self.severity().default_weight()
+    }
+
+    fn info(&self) -> CheckInfo {
+        CheckInfo {
+            id: self.id().into(),
+            label: self.label().into(),
+            category: self.category(),
+            depends_on: self.depends_on().iter().map(|d| d.to_string()).collect(),
+            severity: self.severity(),
+            weight: self.weight(),
+        }
+    }
+}
+
+/// Receives progress notifications while a scan runs.
+pub trait ScanObserver {
+    fn check_started(&self, _info: &CheckInfo) {}
+    fn check_finished(&self, _result: &CheckResult) {}
+}
+
+/// Observer for scans nobody is watching.
+pub struct NoopObserver;
+
+impl ScanObserver for NoopObserver {}
+
+/// Ordered collection of the checks that make up a scan.
+#[derive(Default)]
+pub struct CheckRegistry {
+    checks: Vec<Arc<dyn Check>>,
+}
+
+impl CheckRegistry {
+    /// Registry containing every check shipped with the tool.
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        registry.register(joplin_check::JoplinCheck);
+        registry.register(os_check::OsCheck);
+        registry.register(onenote_check::OneNoteCheck);
+        registry.register(word_check::WordCheck);
+        registry.register(sync_check::AutoSyncCheck);
+        registry.register(sync_check::FullDownloadCheck);
+        registry.register(disk_check::DiskSpaceCheck);
+        registry.register(section_check::LockedSectionsCheck);
+        registry
+    }
+
+    /// The built-in checks followed by the generic rules from `policy`.
+    pub fn with_policy(policy: &Policy) -> Self {
+        let mut registry = Self::builtin();
+        for rule in &policy.rules {
+            registry.register(rule_check::RegistryRuleCheck::new(rule.clone()));
+        }
+        registry
+    }
+
+    pub fn register<C: Check + 'static>(&mut self, check: C) {
+        self.checks.push(Arc::new(check));
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Arc<dyn Check>> {
+        self.checks.iter().find(|c| c.id() == id)
+    }
+
+    /// The checks in `ids` together with everything they transitively depend
+    /// on, in registration order. Unknown ids are an error.
+    pub fn select<S: AsRef<str>>(&self, ids: &[S]) -> Result<Self, ScanError> {
+        let mut needed: Vec<String> = ids.iter().map(|id| id.as_ref().to_string()).collect();
+        let mut i = 0;
+        while i < needed.len() {
+            let check = self.get(&needed[i]).ok_or_else(|| {
+                ScanError::Unexpected(format!("Unknown check '{}'", needed[i]))
+            })?;
+            for dep in check.depends_on() {
+                if !needed.iter().any(|n| n == dep) {
+                    needed.push(dep.to_string());
+                }
+            }
+            i += 1;
+        }
+        Ok(Self {
+            checks: self
+                .checks
+                .iter()
+                .filter(|c| needed.iter().any(|n| n == c.id()))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<dyn Check>> {
+        self.checks.iter()
+    }
+
+    pub fn checks(&self) -> &[Arc<dyn Check>] {
+        &self.checks
+    }
+
+    /// Descriptions of every check, labelled in `locale`.
+    pub fn infos(&self, locale: Locale) -> Vec<CheckInfo> {
+        self.iter().map(|c| locale.check_info(c.as_ref())).collect()
+    }
+}
+
+/// Scan the live system. `only` limits the scan to those checks and their
+/// prerequisites; empty runs every check.
+pub fn run_all_checks(
+    policy: Arc<Policy>,
+    locale: Locale,
+    only: &[String],
+    timeout: Duration,
+    cancel: CancelToken,
+    observer: &dyn ScanObserver,
+) -> Result<ScanResult, ScanError> {
+    let mut registry = CheckRegistry::with_policy(&policy);
+    if !only.is_empty() {
+        registry = registry.select(only)?;
+    }
+    let ctx = ScanContext::system()
+        .with_timeout(timeout)
+        .with_cancel_token(cancel)
+        .with_policy(policy)
+        .with_locale(locale);
+    run_checks(&registry, &Arc::new(ctx), observer)
+}
+
+/// Run every registered check, independent checks in parallel, each under the
+/// context's timeout. Results keep registration order. Stops with
+/// [`ScanError::Cancelled`] as soon as the context's cancel token fires.
+pub fn run_checks(
+    registry: &CheckRegistry,
+    ctx: &Arc<ScanContext>,
+    observer: &dyn ScanObserver,
+) -> Result<ScanResult, ScanError> {
+    let started = Instant::now();
+    let checks = scheduler::run_parallel(registry.checks(), ctx, observer)?;
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let overall = if checks
+        .iter()
+        .any(|c| c.status == CheckStatus::Fail || c.status == CheckStatus::Timeout)
+    {
         CheckStatus::Fail
     } else if checks.iter().any(|c| c.status == CheckStatus::Warning) {
         CheckStatus::Warning
@@ -27,23 +378,141 @@ pub fn run_all_checks() -> Result<ScanResult, ScanError> {
         CheckStatus::Pass
     };
 
-    let os_info = get_os_info();
+    let score = readiness_score(&checks);
+    let os_info = get_os_info(ctx);
     let timestamp = chrono::Utc::now().to_rfc3339();
 
     Ok(ScanResult {
         checks,
         timestamp,
+        duration_ms,
         os_info,
         overall,
+        score,
+        locale: ctx.locale(),
+        redacted: false,
+        inventory: ctx.inventory.get().and_then(|i| i.as_ref().ok()).cloned(),
     })
 }
 
-#[cfg(windows)]
-fn get_os_info() -> String {
-    os_check::get_os_display_string()
+/// Run a single check, after its prerequisites, e.g. to confirm a fix.
+pub fn run_check(
+    registry: &CheckRegistry,
+    id: &str,
+    ctx: &Arc<ScanContext>,
+) -> Result<CheckResult, ScanError> {
+    let subset = registry.select(&[id])?;
+    scheduler::run_parallel(subset.checks(), ctx, &NoopObserver)?
+        .into_iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| ScanError::Unexpected(format!("Check '{}' produced no result", id)))
 }
 
-#[cfg(not(windows))]
-fn get_os_info() -> String {
-    "Non-Windows OS (checks unavailable)".to_string()
+/// Weighted share of the checks that are in order, from 0 to 100. A pass
+/// earns a check's full weight and a warning half of it; failures and
+/// timeouts earn nothing. Skipped checks do not count either way, so there is
+/// no score when every weighted check was skipped.
+pub fn readiness_score(checks: &[CheckResult]) -> Option<u8> {
+    let (earned, total) = checks
+        .iter()
+        .filter(|c| c.status != CheckStatus::Skipped)
+        .fold((0u32, 0u32), |(earned, total), c| {
+            let credit = match c.status {
+                CheckStatus::Pass => c.weight * 2,
+                CheckStatus::Warning => c.weight,
+                _ => 0,
+            };
+            (earned + credit, total + c.weight * 2)
+        });
+    if total == 0 {
+        return None;
+    }
+    Some((earned * 100 / total) as u8)
+}
+
+fn get_os_info(ctx: &ScanContext) -> String {
+    if ctx.is_supported() {
+        os_check::get_os_display_string(ctx)
+    } else {
+        "Non-Windows OS (checks unavailable)".to_string()
+    }
+}
+
+/// Building blocks for the checks' unit tests.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use super::host::FakeHost;
+    use super::registry::{FakeRegistry, Hive, RegValue};
+    use super::ScanContext;
+
+    /// `GetHierarchy` output for two notebooks, one with nested section
+    /// groups, a recycle bin and password-protected and read-only sections.
+    pub const HIERARCHY: &str = include_str!("../../tests/fixtures/hierarchy.xml");
+
+    pub fn context(registry: FakeRegistry, host: FakeHost) -> ScanContext {
+        ScanContext::new(Arc::new(registry), Arc::new(host))
+    }
+
+    /// An empty directory of its own for test `name`.
+    pub fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("onenote-readiness-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// `registry` with an MSI-style `InstallRoot` for `app` of Office `version`.
+    pub fn office_root(registry: FakeRegistry, version: &str, app: &str) -> FakeRegistry {
+        registry.with_value(
+            Hive::LocalMachine,
+            &format!("SOFTWARE\\Microsoft\\Office\\{}\\{}\\InstallRoot", version, app),
+            "Path",
+            RegValue::Sz(format!("C:\\Program Files\\Microsoft Office\\Office{}\\", &version[..2])),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(status: CheckStatus, weight: u32) -> CheckResult {
+        CheckResult {
+            status,
+            weight,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pass_earns_full_weight_and_warning_half() {
+        // 2×40 + 1×20 + 0×20 of 2×(40 + 20 + 20) = 100 of 160.
+        let checks = [
+            check(CheckStatus::Pass, 40),
+            check(CheckStatus::Warning, 20),
+            check(CheckStatus::Fail, 20),
+        ];
+        assert_eq!(readiness_score(&checks), Some(62));
+
+        assert_eq!(readiness_score(&[check(CheckStatus::Pass, 10)]), Some(100));
+        assert_eq!(readiness_score(&[check(CheckStatus::Warning, 10)]), Some(50));
+        assert_eq!(readiness_score(&[check(CheckStatus::Timeout, 10)]), Some(0));
+    }
+
+    #[test]
+    fn skipped_checks_do_not_count() {
+        let checks = [check(CheckStatus::Pass, 20), check(CheckStatus::Skipped, 40)];
+        assert_eq!(readiness_score(&checks), Some(100));
+    }
+
+    #[test]
+    fn no_score_without_weight() {
+        assert_eq!(readiness_score(&[]), None);
+        assert_eq!(readiness_score(&[check(CheckStatus::Skipped, 40)]), None);
+        assert_eq!(readiness_score(&[check(CheckStatus::Pass, 0), check(CheckStatus::Fail, 0)]), None);
+    }
 }
@@ -1,143 +1,254 @@
-use crate::types::{CheckResult, CheckStatus};
+use crate::types::{CheckCategory, CheckResult, CheckStatus, Evidence, RemediationStep, Severity};
 
-#[cfg(windows)]
-pub fn check() -> CheckResult {
-    let registry_info = detect_via_registry();
-    let com_result = super::registry::test_com_activation("OneNote.Application");
+use super::host;
+use super::registry::{self, Hive, RegistryReader};
+use super::{Check, ScanContext};
+
+pub struct OneNoteCheck;
+
+impl Check for OneNoteCheck {
+    fn id(&self) -> &'static str {
+        "onenote"
+    }
+
+    fn label(&self) -> &'static str {
+        "OneNote (Desktop)"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Office
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Blocker
+    }
+
+    fn run(&self, ctx: &ScanContext) -> CheckResult {
+        if !ctx.is_supported() {
+            return unsupported(ctx);
+        }
+        check(ctx)
+    }
+}
+
+pub fn check(ctx: &ScanContext) -> CheckResult {
+    let reg = ctx.registry();
+    let mut evidence = Vec::new();
+    let registry_info = detect_via_registry(ctx, &mut evidence);
+    let com_result = ctx.host().com_activation("OneNote.Application");
+    evidence.push(host::com_evidence("OneNote.Application", &com_result));
 
     match (&registry_info, &com_result) {
         (Some(info), Ok(())) => CheckResult {
             id: "onenote".into(),
             label: "OneNote (Desktop)".into(),
             status: CheckStatus::Pass,
-            message: format!("{} — COM automation verified.", info),
-            remediation: None,
+            message: ctx.t("office.com_verified", &[("install", info)]),
+            remediation: Vec::new(),
+            evidence,
+            ..Default::default()
         },
         (None, Ok(())) => CheckResult {
             id: "onenote".into(),
             label: "OneNote (Desktop)".into(),
             status: CheckStatus::Warning,
-            message: "OneNote COM automation works but installation not found via standard registry paths.".into(),
-            remediation: Some(
-                "The export should work, but your Office installation appears non-standard \
-                 (e.g. Microsoft Store or MSIX deployment). For best reliability, consider \
-                 reinstalling Office as a standard Click-to-Run installation from office.com."
-                    .into(),
-            ),
+            message: ctx.t("office.non_standard", &[("app", &"OneNote")]),
+            remediation: vec![
+                ctx.locale().instruction("office.non_standard.remediation", &[]),
+                RemediationStep::link(ctx.t("office.install", &[]), registry::OFFICE_URL),
+            ],
+            evidence,
+            ..Default::default()
         },
         (Some(info), Err(e)) => CheckResult {
             id: "onenote".into(),
             label: "OneNote (Desktop)".into(),
             status: CheckStatus::Fail,
-            message: format!("{} — but COM automation failed: {}", info, e),
-            remediation: Some(
-                "OneNote is installed but COM automation is not working. Try repairing \
-                 your Office installation: Settings \u{2192} Apps \u{2192} Microsoft Office \
-                 \u{2192} Modify \u{2192} Online Repair."
-                    .into(),
-            ),
+            message: ctx.t("office.com_failed", &[("install", info), ("error", e)]),
+            remediation: vec![
+                ctx.locale().instruction("office.com_failed.remediation", &[("app", &"OneNote")]),
+                ctx.locale().menu(registry::OFFICE_REPAIR_MENU),
+            ],
+            evidence,
+            ..Default::default()
         },
         (None, Err(_)) => {
-            if is_uwp_onenote_installed() {
+            if let Some(package) = find_uwp_onenote_package(reg) {
+                evidence.push(Hive::CurrentUser.evidence(APP_PACKAGES_KEY, "Package", package));
                 CheckResult {
                     id: "onenote".into(),
                     label: "OneNote (Desktop)".into(),
                     status: CheckStatus::Fail,
-                    message: "Only UWP/Store version of OneNote detected. Desktop version is required.".into(),
-                    remediation: Some(
-                        "Install Microsoft Office (Desktop) with OneNote. The Microsoft Store version \
-                         (OneNote for Windows 10) does not support COM automation. You need Office 2013, \
-                         2016, 2019, 2021, or Microsoft 365 desktop apps."
-                            .into(),
-                    ),
+                    message: ctx.t("onenote.store_only", &[]),
+                    remediation: vec![ctx.locale().instruction("onenote.store_only.remediation", &[])],
+                    evidence,
+                    ..Default::default()
                 }
             } else {
                 CheckResult {
                     id: "onenote".into(),
                     label: "OneNote (Desktop)".into(),
                     status: CheckStatus::Fail,
-                    message: "OneNote desktop application not found.".into(),
-                    remediation: Some(
-                        "Install Microsoft Office (Desktop) with OneNote included. \
-                         Office 2013 or later is required."
-                            .into(),
-                    ),
+                    message: ctx.t("onenote.not_found", &[]),
+                    remediation: vec![ctx.locale().instruction("onenote.not_found.remediation", &[])],
+                    evidence,
+                    ..Default::default()
                 }
             }
         }
     }
 }
 
-#[cfg(windows)]
-fn detect_via_registry() -> Option<String> {
-    for version in &["16.0", "15.0"] {
-        if let Some(path) = super::registry::find_office_install_root("OneNote", version) {
-            return Some(format!("Version {} at {}", version, path));
-        }
+fn detect_via_registry(ctx: &ScanContext, evidence: &mut Vec<Evidence>) -> Option<String> {
+    if let Some(install) = ctx.onenote_install() {
+        evidence.push(install.evidence());
+        return Some(ctx.t(
+            "office.install_root",
+            &[("version", &install.version), ("path", &install.path)],
+        ));
     }
-    if let Some(c2r) = super::registry::find_click_to_run() {
-        if !super::registry::is_c2r_app_excluded(&c2r.product_ids, "OneNote") {
-            let ver = c2r.version.as_deref().unwrap_or("unknown");
-            return Some(format!("Click-to-Run {} (v{})", c2r.product_ids, ver));
+    if let Some(c2r) = ctx.click_to_run() {
+        if !registry::is_c2r_app_excluded(ctx.registry(), &c2r.product_ids, "OneNote") {
+            evidence.extend(c2r.evidence());
+            let ver = c2r.version.clone().unwrap_or_else(|| ctx.t("common.unknown", &[]));
+            return Some(ctx.t(
+                "office.click_to_run",
+                &[("products", &c2r.product_ids), ("version", &ver)],
+            ));
         }
     }
     None
 }
 
-#[cfg(windows)]
-fn is_uwp_onenote_installed() -> bool {
-    use windows::Win32::System::Registry::*;
-    use windows::Win32::Foundation::*;
-    use windows::core::*;
-
-    unsafe {
-        let subkey = w!("Software\\Classes\\Local Settings\\Software\\Microsoft\\Windows\\CurrentVersion\\AppModel\\Repository\\Packages");
-        let mut key = HKEY::default();
-        let status = RegOpenKeyExW(HKEY_CURRENT_USER, subkey, 0, KEY_READ, &mut key);
-        if status != ERROR_SUCCESS {
-            return false;
-        }
-
-        let mut index = 0u32;
-        let mut name_buf = vec![0u16; 512];
-        loop {
-            let mut name_len = name_buf.len() as u32;
-            let result = RegEnumKeyExW(
-                key,
-                index,
-                PWSTR::from_raw(name_buf.as_mut_ptr()),
-                &mut name_len,
-                None,
-                PWSTR::null(),
-                None,
-                None,
-            );
-
-            if result != ERROR_SUCCESS {
-                break;
-            }
-
-            let name = String::from_utf16_lossy(&name_buf[..name_len as usize]);
-            if name.contains("OneNote") || name.contains("onenote") {
-                let _ = RegCloseKey(key);
-                return true;
-            }
+const APP_PACKAGES_KEY: &str = "Software\\Classes\\Local Settings\\Software\\Microsoft\\Windows\\CurrentVersion\\AppModel\\Repository\\Packages";
 
-            index += 1;
-        }
-
-        let _ = RegCloseKey(key);
-        false
-    }
+fn find_uwp_onenote_package(reg: &dyn RegistryReader) -> Option<String> {
+    reg.subkeys(Hive::CurrentUser, APP_PACKAGES_KEY)
+        .into_iter()
+        .find(|name| name.contains("OneNote") || name.contains("onenote"))
 }
 
-#[cfg(not(windows))]
-pub fn check() -> CheckResult {
+fn unsupported(ctx: &ScanContext) -> CheckResult {
     CheckResult {
         id: "onenote".into(),
         label: "OneNote (Desktop)".into(),
         status: CheckStatus::Fail,
-        message: "Not running on Windows — cannot check OneNote.".into(),
-        remediation: Some("This tool must be run on Windows.".into()),
+        message: ctx.t("onenote.unsupported", &[]),
+        remediation: vec![ctx.locale().instruction("common.windows_required", &[])],
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::fixtures::{context, office_root};
+    use crate::checks::host::FakeHost;
+    use crate::checks::registry::{FakeRegistry, RegValue};
+    use crate::i18n::Locale;
+
+    const C2R_KEY: &str = "SOFTWARE\\Microsoft\\Office\\ClickToRun\\Configuration";
+
+    fn com(outcome: Result<(), String>) -> FakeHost {
+        FakeHost::new().with_com("OneNote.Application", outcome)
+    }
+
+    fn click_to_run(excluded: Option<&str>) -> FakeRegistry {
+        let sz = |s: &str| RegValue::Sz(s.into());
+        let registry = FakeRegistry::new()
+            .with_value(Hive::LocalMachine, C2R_KEY, "InstallationPath", sz("C:\\Program Files\\Microsoft Office"))
+            .with_value(Hive::LocalMachine, C2R_KEY, "ProductReleaseIds", sz("O365ProPlusRetail"))
+            .with_value(Hive::LocalMachine, C2R_KEY, "VersionToReport", sz("16.0.17928.20114"));
+        match excluded {
+            Some(apps) => registry.with_value(Hive::LocalMachine, C2R_KEY, "O365ProPlusRetail.ExcludedApps", sz(apps)),
+            None => registry,
+        }
+    }
+
+    #[test]
+    fn install_root_with_com_passes() {
+        let registry = office_root(FakeRegistry::new(), "16.0", "OneNote");
+        let result = check(&context(registry, com(Ok(()))));
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert_eq!(result.evidence[0].key, "Path");
+    }
+
+    #[test]
+    fn install_root_without_com_fails() {
+        let registry = office_root(FakeRegistry::new(), "16.0", "OneNote");
+        let result = check(&context(registry, com(Err("CoCreateInstance: 0x80040154".into()))));
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.message.contains("0x80040154"));
+    }
+
+    #[test]
+    fn click_to_run_with_com_passes() {
+        let result = check(&context(click_to_run(None), com(Ok(()))));
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert!(result.evidence.iter().any(|e| e.key == "ProductReleaseIds"));
+    }
+
+    #[test]
+    fn notebooks_are_not_read() {
+        let registry = office_root(FakeRegistry::new(), "16.0", "OneNote");
+        let host = com(Ok(())).with_hierarchy(Err("RPC server unavailable".into()));
+        let ctx = context(registry, host);
+        let result = check(&ctx);
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert!(result.evidence.iter().all(|e| e.key != "GetHierarchy"));
+        assert!(ctx.inventory.get().is_none());
+    }
+
+    #[test]
+    fn com_without_registry_warns() {
+        let result = check(&context(FakeRegistry::new(), com(Ok(()))));
+        assert_eq!(result.status, CheckStatus::Warning);
+    }
+
+    #[test]
+    fn excluded_from_click_to_run_is_not_found() {
+        let result = check(&context(click_to_run(Some("Groove,OneNote")), com(Err("no class".into()))));
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert_eq!(result.message, Locale::En.t("onenote.not_found", &[]));
+    }
+
+    #[test]
+    fn store_app_only_fails() {
+        let registry = FakeRegistry::new().with_key(
+            Hive::CurrentUser,
+            &format!("{}\\Microsoft.Office.OneNote_16001.14326.21090.0_x64__8wekyb3d8bbwe", APP_PACKAGES_KEY),
+        );
+        let result = check(&context(registry, com(Err("no class".into()))));
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert_eq!(result.message, Locale::En.t("onenote.store_only", &[]));
+    }
+
+    #[test]
+    fn office_detection_falls_back_to_older_versions() {
+        let registry = office_root(FakeRegistry::new(), "15.0", "OneNote");
+        let versions = vec!["16.0".to_string(), "15.0".to_string()];
+        let install = registry::detect_office_install(&registry, "OneNote", &versions).unwrap();
+        assert_eq!(install.version, "15.0");
+
+        let both = office_root(registry, "16.0", "OneNote");
+        let install = registry::detect_office_install(&both, "OneNote", &versions).unwrap();
+        assert_eq!(install.version, "16.0");
+
+        let only_newer = vec!["16.0".to_string()];
+        let older = office_root(FakeRegistry::new(), "15.0", "OneNote");
+        assert!(registry::detect_office_install(&older, "OneNote", &only_newer).is_none());
+    }
+
+    #[test]
+    fn office_detection_reads_32_bit_office_on_64_bit_windows() {
+        let registry = FakeRegistry::new().with_value(
+            Hive::LocalMachine,
+            "SOFTWARE\\WOW6432Node\\Microsoft\\Office\\16.0\\OneNote\\InstallRoot",
+            "Path",
+            RegValue::Sz("C:\\Program Files (x86)\\Microsoft Office\\root\\Office16\\".into()),
+        );
+        let ctx = context(registry, FakeHost::new());
+        let install = ctx.onenote_install().unwrap();
+        assert!(install.key.starts_with("SOFTWARE\\WOW6432Node"));
     }
 }
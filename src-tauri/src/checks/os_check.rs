@@ -1,182 +1,245 @@
-use crate::types::{CheckResult, CheckStatus};
+use crate::types::{CheckCategory, CheckResult, CheckStatus, Evidence};
 
-#[cfg(windows)]
-pub fn check() -> CheckResult {
-    let (build, display_version, product_name, ubr) = match read_os_version() {
+use super::registry::{Hive, RegistryReader};
+use super::{Check, ScanContext};
+
+pub struct OsCheck;
+
+impl Check for OsCheck {
+    fn id(&self) -> &'static str {
+        "windows_os"
+    }
+
+    fn label(&self) -> &'static str {
+        "Windows OS"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::System
+    }
+
+    fn run(&self, ctx: &ScanContext) -> CheckResult {
+        if !ctx.is_supported() {
+            return unsupported(ctx);
+        }
+        check(ctx)
+    }
+}
+
+pub fn check(ctx: &ScanContext) -> CheckResult {
+    let os = match ctx.os_version() {
         Ok(v) => v,
         Err(e) => {
             return CheckResult {
                 id: "windows_os".into(),
                 label: "Windows OS".into(),
                 status: CheckStatus::Fail,
-                message: format!("Could not read OS version: {}", e),
-                remediation: Some("Ensure you are running Windows 10 or 11.".into()),
+                message: ctx.t("windows_os.unreadable", &[("error", &e)]),
+                remediation: vec![ctx.locale().instruction("windows_os.unreadable.remediation", &[])],
+                ..Default::default()
             };
         }
     };
 
-    let build_num: u32 = build.parse().unwrap_or(0);
+    let build_num = os.build_number();
+    let description = os.description();
+    let evidence = os.evidence();
+    let thresholds = &ctx.policy().windows;
+    let required = required_version(ctx, thresholds.min_build);
 
-    let product_name = if build_num >= 22000 {
-        product_name.replace("Windows 10", "Windows 11")
-    } else {
-        product_name
-    };
-
-    let build_str = match ubr {
-        Some(u) => format!("{}.{}", build, u),
-        None => build.clone(),
-    };
-
-    let version_suffix = if display_version.is_empty() {
-        String::new()
-    } else {
-        format!(" {}", display_version)
-    };
-
-    if build_num >= 19041 {
+    if build_num >= thresholds.min_build {
         CheckResult {
             id: "windows_os".into(),
             label: "Windows OS".into(),
             status: CheckStatus::Pass,
-            message: format!("{}{} (Build {})", product_name, version_suffix, build_str),
-            remediation: None,
+            message: description,
+            remediation: Vec::new(),
+            evidence,
+            ..Default::default()
         }
-    } else if build_num >= 10240 {
+    } else if build_num >= thresholds.min_supported_build {
         CheckResult {
             id: "windows_os".into(),
             label: "Windows OS".into(),
             status: CheckStatus::Warning,
-            message: format!("{}{} (Build {}) - consider updating", product_name, version_suffix, build_str),
-            remediation: Some(
-                "Update Windows 10 to version 2004 or later via Windows Update.".into(),
-            ),
+            message: ctx.t("windows_os.outdated", &[("description", &description)]),
+            remediation: vec![
+                ctx.locale().instruction("windows_os.outdated.remediation", &[("required", &required)]),
+                ctx.locale().menu(&["menu.settings", "menu.windows_update"]),
+            ],
+            evidence,
+            ..Default::default()
         }
     } else {
         CheckResult {
             id: "windows_os".into(),
             label: "Windows OS".into(),
             status: CheckStatus::Fail,
-            message: format!("Unsupported OS: {}{} (Build {})", product_name, version_suffix, build_str),
-            remediation: Some("Windows 10 (version 2004+) or Windows 11 is required.".into()),
+            message: ctx.t("windows_os.unsupported_version", &[("description", &description)]),
+            remediation: vec![ctx
+                .locale()
+                .instruction("windows_os.unsupported_version.remediation", &[("required", &required)])],
+            evidence,
+            ..Default::default()
         }
     }
 }
 
-#[cfg(windows)]
-fn read_os_version() -> Result<(String, String, String, Option<u32>), String> {
-    use windows::Win32::System::Registry::*;
-    use windows::Win32::Foundation::*;
-    use windows::core::*;
-
-    unsafe {
-        let subkey = w!("SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion");
-        let mut key = HKEY::default();
-        let status = RegOpenKeyExW(HKEY_LOCAL_MACHINE, subkey, 0, KEY_READ, &mut key);
-        if status != ERROR_SUCCESS {
-            return Err(format!("RegOpenKeyExW failed: {:?}", status));
-        }
+/// How to name the minimum build in advice, e.g. "version 2004" for 19041.
+fn required_version(ctx: &ScanContext, min_build: u32) -> String {
+    let version = match min_build {
+        19041 => "2004",
+        19042 => "20H2",
+        19043 => "21H1",
+        19044 => "21H2",
+        19045 => "22H2",
+        build => return ctx.t("windows_os.build", &[("build", &build)]),
+    };
+    ctx.t("windows_os.version", &[("version", &version)])
+}
+
+const CURRENT_VERSION_KEY: &str = "SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion";
+
+/// Windows version as recorded under `HKLM\SOFTWARE\Microsoft\Windows NT\CurrentVersion`.
+#[derive(Debug, Clone)]
+pub struct OsVersion {
+    pub build: String,
+    pub display_version: String,
+    pub product_name: String,
+    pub ubr: Option<u32>,
+}
 
-        let build = read_reg_string(key, w!("CurrentBuild")).unwrap_or_default();
-        let display = read_reg_string(key, w!("DisplayVersion")).unwrap_or_default();
-        let product = read_reg_string(key, w!("ProductName")).unwrap_or_default();
+impl OsVersion {
+    pub fn build_number(&self) -> u32 {
+        self.build.parse().unwrap_or(0)
+    }
 
-        let _ = RegCloseKey(key);
+    /// Product name, corrected for Windows 11, which still reports itself as
+    /// "Windows 10" in `ProductName`.
+    pub fn product(&self) -> String {
+        if self.build_number() >= 22000 {
+            self.product_name.replace("Windows 10", "Windows 11")
+        } else {
+            self.product_name.clone()
+        }
+    }
 
-        let ubr = super::registry::read_reg_dword(
-            HKEY_LOCAL_MACHINE,
-            "SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion",
-            "UBR",
-        );
+    pub fn evidence(&self) -> Vec<Evidence> {
+        let mut evidence = vec![
+            Hive::LocalMachine.evidence(CURRENT_VERSION_KEY, "ProductName", self.product_name.as_str()),
+            Hive::LocalMachine.evidence(CURRENT_VERSION_KEY, "CurrentBuild", self.build.as_str()),
+        ];
+        if !self.display_version.is_empty() {
+            evidence.push(Hive::LocalMachine.evidence(
+                CURRENT_VERSION_KEY,
+                "DisplayVersion",
+                self.display_version.as_str(),
+            ));
+        }
+        if let Some(ubr) = self.ubr {
+            evidence.push(Hive::LocalMachine.evidence(CURRENT_VERSION_KEY, "UBR", ubr.to_string()));
+        }
+        evidence
+    }
 
-        Ok((build, display, product, ubr))
+    /// E.g. "Windows 11 Pro 23H2 (Build 22631.2861)".
+    pub fn description(&self) -> String {
+        let build_str = match self.ubr {
+            Some(u) => format!("{}.{}", self.build, u),
+            None => self.build.clone(),
+        };
+        if self.display_version.is_empty() {
+            format!("{} (Build {})", self.product(), build_str)
+        } else {
+            format!("{} {} (Build {})", self.product(), self.display_version, build_str)
+        }
     }
 }
 
-#[cfg(windows)]
-unsafe fn read_reg_string(
-    key: windows::Win32::System::Registry::HKEY,
-    value_name: windows::core::PCWSTR,
-) -> Result<String, String> {
-    use windows::Win32::System::Registry::*;
-    use windows::Win32::Foundation::*;
-
-    let mut buf_size: u32 = 0;
-    let mut value_type = REG_VALUE_TYPE::default();
-
-    let _ = RegQueryValueExW(
-        key,
-        value_name,
-        None,
-        Some(&mut value_type),
-        None,
-        Some(&mut buf_size),
-    );
-
-    if buf_size == 0 {
-        return Err("Empty value".into());
-    }
-
-    let mut buffer = vec![0u8; buf_size as usize];
-    let status = RegQueryValueExW(
-        key,
-        value_name,
-        None,
-        Some(&mut value_type),
-        Some(buffer.as_mut_ptr()),
-        Some(&mut buf_size),
-    );
-
-    if status != ERROR_SUCCESS {
-        return Err(format!("RegQueryValueExW failed: {:?}", status));
-    }
-
-    let wide: Vec<u16> = buffer
-        .chunks_exact(2)
-        .map(|c| u16::from_le_bytes([c[0], c[1]]))
-        .collect();
-    Ok(String::from_utf16_lossy(&wide)
-        .trim_end_matches('\0')
-        .to_string())
+pub fn read_os_version(reg: &dyn RegistryReader) -> Result<OsVersion, String> {
+    if !reg.open_key(Hive::LocalMachine, CURRENT_VERSION_KEY) {
+        return Err(format!("cannot open HKLM\\{}", CURRENT_VERSION_KEY));
+    }
+
+    let read_sz = |name: &str| {
+        reg.read_string(Hive::LocalMachine, CURRENT_VERSION_KEY, name)
+            .unwrap_or_default()
+    };
+
+    Ok(OsVersion {
+        build: read_sz("CurrentBuild"),
+        display_version: read_sz("DisplayVersion"),
+        product_name: read_sz("ProductName"),
+        ubr: reg.read_dword(Hive::LocalMachine, CURRENT_VERSION_KEY, "UBR"),
+    })
 }
 
-#[cfg(windows)]
-pub fn get_os_display_string() -> String {
-    match read_os_version() {
-        Ok((build, display, product, ubr)) => {
-            let build_num: u32 = build.parse().unwrap_or(0);
-            let product = if build_num >= 22000 {
-                product.replace("Windows 10", "Windows 11")
-            } else {
-                product
-            };
-            let build_str = match ubr {
-                Some(u) => format!("{}.{}", build, u),
-                None => build,
-            };
-            if display.is_empty() {
-                format!("{} (Build {})", product, build_str)
-            } else {
-                format!("{} {} (Build {})", product, display, build_str)
-            }
-        },
-        Err(_) => "Windows (version unknown)".into(),
+pub fn get_os_display_string(ctx: &ScanContext) -> String {
+    match ctx.os_version() {
+        Ok(os) => os.description(),
+        Err(_) => ctx.t("windows_os.unknown_version", &[]),
     }
 }
 
-#[cfg(not(windows))]
-pub fn check() -> CheckResult {
+fn unsupported(ctx: &ScanContext) -> CheckResult {
     CheckResult {
         id: "windows_os".into(),
         label: "Windows OS".into(),
         status: CheckStatus::Fail,
-        message: "Not running on Windows".into(),
-        remediation: Some("This tool must be run on Windows 10 or 11.".into()),
+        message: ctx.t("windows_os.not_windows", &[]),
+        remediation: vec![ctx.locale().instruction("windows_os.not_windows.remediation", &[])],
+        ..Default::default()
     }
 }
 
-#[allow(dead_code)]
-#[cfg(not(windows))]
-pub fn get_os_display_string() -> String {
-    "Non-Windows OS".to_string()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::fixtures::context;
+    use crate::checks::host::FakeHost;
+    use crate::checks::registry::{FakeRegistry, RegValue};
+
+    fn windows(build: &str, display_version: &str) -> FakeRegistry {
+        let sz = |s: &str| RegValue::Sz(s.into());
+        FakeRegistry::new()
+            .with_value(Hive::LocalMachine, CURRENT_VERSION_KEY, "ProductName", sz("Windows 10 Pro"))
+            .with_value(Hive::LocalMachine, CURRENT_VERSION_KEY, "CurrentBuild", sz(build))
+            .with_value(Hive::LocalMachine, CURRENT_VERSION_KEY, "DisplayVersion", sz(display_version))
+            .with_value(Hive::LocalMachine, CURRENT_VERSION_KEY, "UBR", RegValue::Dword(2861))
+    }
+
+    #[test]
+    fn current_build_passes_and_reports_windows_11() {
+        let result = check(&context(windows("22631", "23H2"), FakeHost::new()));
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert_eq!(result.message, "Windows 11 Pro 23H2 (Build 22631.2861)");
+        assert_eq!(result.evidence.len(), 4);
+    }
+
+    #[test]
+    fn old_but_supported_build_warns() {
+        let result = check(&context(windows("17763", "1809"), FakeHost::new()));
+        assert_eq!(result.status, CheckStatus::Warning);
+    }
+
+    #[test]
+    fn unsupported_build_fails() {
+        let result = check(&context(windows("9600", ""), FakeHost::new()));
+        assert_eq!(result.status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn missing_key_fails() {
+        let result = check(&context(FakeRegistry::new(), FakeHost::new()));
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.evidence.is_empty());
+    }
+
+    #[test]
+    fn missing_values_read_as_empty() {
+        let registry = FakeRegistry::new().with_key(Hive::LocalMachine, CURRENT_VERSION_KEY);
+        let os = read_os_version(&registry).unwrap();
+        assert_eq!(os.build_number(), 0);
+        assert_eq!(os.ubr, None);
+    }
 }
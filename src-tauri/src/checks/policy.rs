@@ -0,0 +1,332 @@
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{ScanError, Severity};
+
+use super::registry::{Hive, RegValue, OFFICE_VERSIONS};
+
+/// Environment variable naming a policy file to use instead of the default locations.
+pub const POLICY_ENV_VAR: &str = "ONENOTE_READINESS_POLICY";
+
+const POLICY_FILE_NAME: &str = "policy.json";
+const PROGRAM_DATA_DIR: &str = "OneNote to Joplin Readiness";
+
+/// Site-specific thresholds and extra rules. Every field is optional in the
+/// file; anything left out keeps the built-in default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct Policy {
+    pub windows: WindowsPolicy,
+    /// Office versions that count as a supported desktop install, newest first.
+    pub office_versions: Vec<String>,
+    pub sync: SyncPolicy,
+    pub disk: DiskPolicy,
+    /// Additional "registry value must equal" checks, run after the built-in ones.
+    pub rules: Vec<RegistryRule>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            windows: WindowsPolicy::default(),
+            office_versions: OFFICE_VERSIONS.iter().map(|v| v.to_string()).collect(),
+            sync: SyncPolicy::default(),
+            disk: DiskPolicy::default(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// Builds at or above `min_build` pass, builds at or above
+/// `min_supported_build` warn, anything older fails.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct WindowsPolicy {
+    pub min_build: u32,
+    pub min_supported_build: u32,
+}
+
+impl Default for WindowsPolicy {
+    fn default() -> Self {
+        Self {
+            min_build: 19041,
+            min_supported_build: 10240,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct SyncPolicy {
+    /// `SyncStateOffline` under `OneNote\Options\Save`.
+    pub auto_sync: DwordPolicy,
+    /// `DeferFdoDownload` under `OneNote\Options`.
+    pub full_download: DwordPolicy,
+}
+
+impl Default for SyncPolicy {
+    fn default() -> Self {
+        Self {
+            auto_sync: DwordPolicy {
+                pass_values: vec![0],
+                pass_if_missing: true,
+            },
+            full_download: DwordPolicy {
+                pass_values: vec![0],
+                pass_if_missing: false,
+            },
+        }
+    }
+}
+
+/// Where notebooks will be exported, for the disk space check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct DiskPolicy {
+    /// Export destination; `%NAME%` environment references are expanded.
+    /// The user's Documents folder if not set.
+    pub export_folder: Option<String>,
+    /// Room to leave beyond the estimated size, as a percentage of it. Less
+    /// than that warns; less than the estimate itself fails.
+    pub headroom_percent: u32,
+}
+
+impl Default for DiskPolicy {
+    fn default() -> Self {
+        Self {
+            export_folder: None,
+            headroom_percent: 20,
+        }
+    }
+}
+
+/// Which values of a DWORD setting count as a pass.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct DwordPolicy {
+    pub pass_values: Vec<u32>,
+    pub pass_if_missing: bool,
+}
+
+impl DwordPolicy {
+    pub fn passes(&self, value: Option<u32>) -> bool {
+        match value {
+            Some(v) => self.pass_values.contains(&v),
+            None => self.pass_if_missing,
+        }
+    }
+}
+
+/// A generic check: the registry value `hive\path!name` must equal `equals`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RegistryRule {
+    pub id: String,
+    pub label: String,
+    pub hive: Hive,
+    pub path: String,
+    pub name: String,
+    pub equals: RuleValue,
+    /// Blocker and major rules fail when violated; minor and info rules warn.
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(default)]
+    pub remediation: Option<String>,
+}
+
+/// Expected value of a [`RegistryRule`]: a number for DWORD/QWORD values,
+/// a string for string values (compared case-insensitively).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RuleValue {
+    Number(u64),
+    Text(String),
+}
+
+impl RuleValue {
+    pub fn matches(&self, actual: &RegValue) -> bool {
+        match (self, actual) {
+            (RuleValue::Number(n), RegValue::Dword(v)) => *n == u64::from(*v),
+            (RuleValue::Number(n), RegValue::Qword(v)) => n == v,
+            (RuleValue::Text(s), RegValue::Sz(v)) => s.eq_ignore_ascii_case(v),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for RuleValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleValue::Number(n) => write!(f, "{}", n),
+            RuleValue::Text(s) => write!(f, "\"{}\"", s),
+        }
+    }
+}
+
+impl Policy {
+    pub fn load(path: &Path) -> Result<Self, ScanError> {
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            ScanError::Unexpected(format!("Failed to read policy {}: {}", path.display(), e))
+        })?;
+        let policy: Policy = serde_json::from_str(&json).map_err(|e| {
+            ScanError::Unexpected(format!("Invalid policy {}: {}", path.display(), e))
+        })?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    /// The policy in effect for this machine: the file named by
+    /// [`POLICY_ENV_VAR`], else `%ProgramData%\OneNote to Joplin Readiness\policy.json`,
+    /// else `policy.json` next to the executable, else the built-in defaults.
+    pub fn load_configured() -> Result<Self, ScanError> {
+        Self::load_first(std::env::var_os(POLICY_ENV_VAR).map(PathBuf::from), &config_dirs())
+    }
+
+    /// The file at `named` if given, else `policy.json` in the first of
+    /// `dirs` that has one, else the defaults.
+    fn load_first(named: Option<PathBuf>, dirs: &[PathBuf]) -> Result<Self, ScanError> {
+        if let Some(path) = named {
+            return Self::load(&path);
+        }
+        match dirs.iter().map(|dir| dir.join(POLICY_FILE_NAME)).find(|p| p.is_file()) {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    fn validate(&self) -> Result<(), ScanError> {
+        let invalid = |msg: String| Err(ScanError::Unexpected(format!("Invalid policy: {}", msg)));
+
+        if self.office_versions.is_empty() {
+            return invalid("officeVersions must list at least one version".into());
+        }
+        if self.windows.min_supported_build > self.windows.min_build {
+            return invalid("windows.minSupportedBuild is higher than windows.minBuild".into());
+        }
+
+        let builtin: HashSet<String> = super::CheckRegistry::builtin()
+            .iter()
+            .map(|c| c.id().to_string())
+            .collect();
+        let mut seen = HashSet::new();
+        for rule in &self.rules {
+            if rule.id.trim().is_empty() {
+                return invalid(format!("rule '{}' has an empty id", rule.label));
+            }
+            if builtin.contains(&rule.id) || !seen.insert(rule.id.as_str()) {
+                return invalid(format!("rule id '{}' is already in use", rule.id));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Folders searched for site configuration, in order of precedence:
+/// `%ProgramData%\OneNote to Joplin Readiness`, then the executable's folder.
+pub fn config_dirs() -> Vec<PathBuf> {
+    let exe_dir = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf));
+    search_dirs(std::env::var_os("PROGRAMDATA"), exe_dir)
+}
+
+fn search_dirs(program_data: Option<OsString>, exe_dir: Option<PathBuf>) -> Vec<PathBuf> {
+    program_data
+        .map(|program_data| PathBuf::from(program_data).join(PROGRAM_DATA_DIR))
+        .into_iter()
+        .chain(exe_dir)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::fixtures::temp_dir;
+
+    /// What [`Policy::load`] makes of a file holding `json`.
+    fn parse(json: &str) -> Result<Policy, String> {
+        let policy: Policy = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        policy.validate().map_err(|e| match e {
+            ScanError::Unexpected(message) => message,
+            other => other.to_string(),
+        })?;
+        Ok(policy)
+    }
+
+    fn rule(id: &str) -> String {
+        format!(
+            r#"{{"id": "{}", "label": "Rule", "hive": "HKCU", "path": "Software\\Test", "name": "Value", "equals": 1}}"#,
+            id
+        )
+    }
+
+    #[test]
+    fn fields_left_out_keep_their_defaults() {
+        let json = format!(r#"{{"windows": {{"minBuild": 22000}}, "rules": [{}]}}"#, rule("mine"));
+        let policy = parse(&json).unwrap();
+        assert_eq!(policy.windows.min_build, 22000);
+        assert_eq!(policy.windows.min_supported_build, 10240);
+        assert_eq!(policy.disk.headroom_percent, 20);
+        assert_eq!(policy.rules[0].severity, Severity::Major);
+        assert_eq!(policy.rules[0].equals, RuleValue::Number(1));
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let error = parse(r#"{"windows": {"minBuld": 22000}}"#).unwrap_err();
+        assert!(error.contains("unknown field `minBuld`"), "{}", error);
+        let error = parse(r#"{"officeVersion": ["16.0"]}"#).unwrap_err();
+        assert!(error.contains("unknown field `officeVersion`"), "{}", error);
+        let json = format!(r#"{{"rules": [{}]}}"#, rule("mine").replace("}", r#", "severty": "minor"}"#));
+        let error = parse(&json).unwrap_err();
+        assert!(error.contains("unknown field `severty`"), "{}", error);
+    }
+
+    #[test]
+    fn rule_ids_must_be_unique_and_not_builtin() {
+        let json = format!(r#"{{"rules": [{}, {}]}}"#, rule("mine"), rule("mine"));
+        assert_eq!(parse(&json).unwrap_err(), "Invalid policy: rule id 'mine' is already in use");
+        let json = format!(r#"{{"rules": [{}]}}"#, rule("onenote"));
+        assert_eq!(parse(&json).unwrap_err(), "Invalid policy: rule id 'onenote' is already in use");
+        let json = format!(r#"{{"rules": [{}]}}"#, rule(" "));
+        assert_eq!(parse(&json).unwrap_err(), "Invalid policy: rule 'Rule' has an empty id");
+    }
+
+    #[test]
+    fn build_thresholds_must_be_in_order() {
+        let error = parse(r#"{"windows": {"minBuild": 19041, "minSupportedBuild": 22000}}"#).unwrap_err();
+        assert_eq!(error, "Invalid policy: windows.minSupportedBuild is higher than windows.minBuild");
+        let error = parse(r#"{"officeVersions": []}"#).unwrap_err();
+        assert_eq!(error, "Invalid policy: officeVersions must list at least one version");
+    }
+
+    #[test]
+    fn the_named_file_comes_first_then_program_data_then_the_exe_folder() {
+        let root = temp_dir("policy-lookup");
+        let dirs = search_dirs(Some(root.join("ProgramData").into_os_string()), Some(root.join("bin")));
+        assert_eq!(dirs, [root.join("ProgramData").join(PROGRAM_DATA_DIR), root.join("bin")]);
+        let write = |path: &Path, version: &str| {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, format!(r#"{{"officeVersions": ["{}"]}}"#, version)).unwrap();
+        };
+        let named = root.join("site.json");
+        write(&named, "14.0");
+        write(&dirs[0].join(POLICY_FILE_NAME), "15.0");
+        write(&dirs[1].join(POLICY_FILE_NAME), "16.0");
+        let versions = |named: Option<PathBuf>| Policy::load_first(named, &dirs).unwrap().office_versions;
+
+        assert_eq!(versions(Some(named.clone())), ["14.0"]);
+        assert_eq!(versions(None), ["15.0"]);
+        std::fs::remove_file(dirs[0].join(POLICY_FILE_NAME)).unwrap();
+        assert_eq!(versions(None), ["16.0"]);
+        std::fs::remove_file(dirs[1].join(POLICY_FILE_NAME)).unwrap();
+        assert_eq!(versions(None), Policy::default().office_versions);
+
+        // A named file that is missing is an error, not a fallback.
+        std::fs::remove_file(&named).unwrap();
+        assert!(Policy::load_first(Some(named), &dirs).is_err());
+        assert_eq!(search_dirs(None, Some(root.join("bin"))), [root.join("bin")]);
+    }
+}
@@ -1,209 +1,618 @@
-#[cfg(windows)]
-pub fn find_office_install_root(app: &str, version: &str) -> Option<String> {
-    use windows::Win32::System::Registry::*;
-    use windows::Win32::Foundation::*;
-    use windows::core::*;
+use std::collections::{BTreeMap, HashMap};
 
-    let paths = [
-        format!(
-            "SOFTWARE\\Microsoft\\Office\\{}\\{}\\InstallRoot",
-            version, app
-        ),
-        format!(
-            "SOFTWARE\\WOW6432Node\\Microsoft\\Office\\{}\\{}\\InstallRoot",
-            version, app
-        ),
-    ];
+use serde::{Deserialize, Serialize};
 
-    for subkey_str in &paths {
-        unsafe {
-            let subkey: Vec<u16> = subkey_str.encode_utf16().chain(std::iter::once(0)).collect();
-            let mut key = HKEY::default();
-            let status = RegOpenKeyExW(
-                HKEY_LOCAL_MACHINE,
-                PCWSTR::from_raw(subkey.as_ptr()),
-                0,
-                KEY_READ,
-                &mut key,
-            );
-            if status != ERROR_SUCCESS {
-                continue;
-            }
+use crate::types::Evidence;
 
-            let value_name = w!("Path");
-            let mut buf_size: u32 = 0;
-            let mut value_type = REG_VALUE_TYPE::default();
-            let _ = RegQueryValueExW(
-                key,
-                value_name,
-                None,
-                Some(&mut value_type),
-                None,
-                Some(&mut buf_size),
-            );
+/// Registry root a key lives under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Hive {
+    #[serde(rename = "HKLM")]
+    LocalMachine,
+    #[serde(rename = "HKCU")]
+    CurrentUser,
+}
 
-            if buf_size > 0 {
-                let mut buffer = vec![0u8; buf_size as usize];
-                let status = RegQueryValueExW(
-                    key,
-                    value_name,
-                    None,
-                    Some(&mut value_type),
-                    Some(buffer.as_mut_ptr()),
-                    Some(&mut buf_size),
-                );
-                if status == ERROR_SUCCESS {
-                    let _ = RegCloseKey(key);
-                    let wide: Vec<u16> = buffer
-                        .chunks_exact(2)
-                        .map(|c| u16::from_le_bytes([c[0], c[1]]))
-                        .collect();
-                    return Some(
-                        String::from_utf16_lossy(&wide)
-                            .trim_end_matches('\0')
-                            .to_string(),
-                    );
-                }
-            }
-            let _ = RegCloseKey(key);
+impl Hive {
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Hive::LocalMachine => "HKLM",
+            Hive::CurrentUser => "HKCU",
         }
     }
-    None
+
+    /// Evidence for the value `name` of key `path` in this hive.
+    pub fn evidence(self, path: &str, name: &str, value: impl Into<String>) -> Evidence {
+        Evidence::new(name, value, format!("{}\\{}", self.prefix(), path))
+    }
 }
 
-#[cfg(not(windows))]
-pub fn find_office_install_root(_app: &str, _version: &str) -> Option<String> {
-    None
+/// A typed registry value. `REG_EXPAND_SZ` is reported as `Sz` without expansion.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum RegValue {
+    Sz(String),
+    Dword(u32),
+    Qword(u64),
+    MultiSz(Vec<String>),
 }
 
-#[cfg(windows)]
-pub fn read_reg_dword(root: windows::Win32::System::Registry::HKEY, subkey: &str, value_name: &str) -> Option<u32> {
-    use windows::Win32::System::Registry::*;
-    use windows::Win32::Foundation::*;
-    use windows::core::*;
+/// Read-only access to the Windows registry.
+///
+/// Checks only talk to the registry through this trait, so their decision
+/// logic can run against [`FakeRegistry`] on any platform.
+pub trait RegistryReader: Send + Sync {
+    /// Whether the key exists and can be opened for reading.
+    fn open_key(&self, hive: Hive, path: &str) -> bool;
 
-    unsafe {
-        let subkey_wide: Vec<u16> = subkey.encode_utf16().chain(std::iter::once(0)).collect();
-        let mut hkey = HKEY::default();
-        let status = RegOpenKeyExW(
-            root,
-            PCWSTR::from_raw(subkey_wide.as_ptr()),
-            0,
-            KEY_READ,
-            &mut hkey,
-        );
-        if status != ERROR_SUCCESS {
-            return None;
-        }
+    fn read_value(&self, hive: Hive, path: &str, name: &str) -> Option<RegValue>;
 
-        let value_wide: Vec<u16> = value_name.encode_utf16().chain(std::iter::once(0)).collect();
-        let mut data: u32 = 0;
-        let mut data_size: u32 = std::mem::size_of::<u32>() as u32;
-        let mut reg_type = REG_VALUE_TYPE::default();
+    /// Names of the direct subkeys of `path`, empty if the key does not exist.
+    fn subkeys(&self, hive: Hive, path: &str) -> Vec<String>;
 
-        let status = RegQueryValueExW(
-            hkey,
-            PCWSTR::from_raw(value_wide.as_ptr()),
-            None,
-            Some(&mut reg_type),
-            Some(&mut data as *mut u32 as *mut u8),
-            Some(&mut data_size),
-        );
+    fn read_string(&self, hive: Hive, path: &str, name: &str) -> Option<String> {
+        match self.read_value(hive, path, name)? {
+            RegValue::Sz(s) => Some(s),
+            _ => None,
+        }
+    }
 
-        let _ = RegCloseKey(hkey);
+    fn read_dword(&self, hive: Hive, path: &str, name: &str) -> Option<u32> {
+        match self.read_value(hive, path, name)? {
+            RegValue::Dword(v) => Some(v),
+            _ => None,
+        }
+    }
 
-        if status == ERROR_SUCCESS && reg_type == REG_DWORD {
-            Some(data)
-        } else {
-            None
+    fn read_qword(&self, hive: Hive, path: &str, name: &str) -> Option<u64> {
+        match self.read_value(hive, path, name)? {
+            RegValue::Qword(v) => Some(v),
+            _ => None,
         }
     }
-}
 
-#[cfg(not(windows))]
-pub fn read_reg_dword(_root: u32, _subkey: &str, _value_name: &str) -> Option<u32> {
-    None
+    fn read_multi_string(&self, hive: Hive, path: &str, name: &str) -> Option<Vec<String>> {
+        match self.read_value(hive, path, name)? {
+            RegValue::MultiSz(v) => Some(v),
+            _ => None,
+        }
+    }
 }
 
-/// Info about a Click-to-Run Office installation
-pub struct ClickToRunInfo {
-    pub install_path: String,
-    pub product_ids: String,
-    pub version: Option<String>,
+/// Write access to the registry. Only used by fixes the user explicitly asks
+/// for; scans never write.
+pub trait RegistryWriter: RegistryReader {
+    /// Create or overwrite `name`, creating `path` if needed.
+    fn write_value(&mut self, hive: Hive, path: &str, name: &str, value: &RegValue) -> Result<(), String>;
+
+    /// Remove `name`. Removing a value that does not exist is not an error.
+    fn delete_value(&mut self, hive: Hive, path: &str, name: &str) -> Result<(), String>;
 }
 
-/// Read a REG_SZ string value from a registry key
+// ---------------------------------------------------------------------------
+// Win32 backend
+// ---------------------------------------------------------------------------
+
+/// Registry backend reading the live system through the Win32 API.
 #[cfg(windows)]
-fn read_reg_string(root: windows::Win32::System::Registry::HKEY, subkey: &str, value_name: &str) -> Option<String> {
-    use windows::Win32::System::Registry::*;
-    use windows::Win32::Foundation::*;
-    use windows::core::*;
+pub struct Win32Registry;
 
-    unsafe {
-        let subkey_wide: Vec<u16> = subkey.encode_utf16().chain(std::iter::once(0)).collect();
-        let mut hkey = HKEY::default();
+#[cfg(windows)]
+impl Win32Registry {
+    unsafe fn open(
+        hive: Hive,
+        path: &str,
+    ) -> Option<windows::Win32::System::Registry::HKEY> {
+        use windows::Win32::Foundation::*;
+        use windows::Win32::System::Registry::*;
+        use windows::core::*;
+
+        let root = match hive {
+            Hive::LocalMachine => HKEY_LOCAL_MACHINE,
+            Hive::CurrentUser => HKEY_CURRENT_USER,
+        };
+        let path_wide: Vec<u16> = path.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut key = HKEY::default();
         let status = RegOpenKeyExW(
             root,
-            PCWSTR::from_raw(subkey_wide.as_ptr()),
+            PCWSTR::from_raw(path_wide.as_ptr()),
             0,
             KEY_READ,
-            &mut hkey,
+            &mut key,
         );
-        if status != ERROR_SUCCESS {
-            return None;
+        if status == ERROR_SUCCESS {
+            Some(key)
+        } else {
+            None
         }
+    }
+
+    unsafe fn query_value(
+        key: windows::Win32::System::Registry::HKEY,
+        name: &str,
+    ) -> Option<RegValue> {
+        use windows::Win32::Foundation::*;
+        use windows::Win32::System::Registry::*;
+        use windows::core::*;
 
-        let value_wide: Vec<u16> = value_name.encode_utf16().chain(std::iter::once(0)).collect();
+        let name_wide: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
         let mut buf_size: u32 = 0;
         let mut value_type = REG_VALUE_TYPE::default();
-        let _ = RegQueryValueExW(
-            hkey,
-            PCWSTR::from_raw(value_wide.as_ptr()),
+        let status = RegQueryValueExW(
+            key,
+            PCWSTR::from_raw(name_wide.as_ptr()),
             None,
             Some(&mut value_type),
             None,
             Some(&mut buf_size),
         );
-
-        if buf_size == 0 {
-            let _ = RegCloseKey(hkey);
+        if status != ERROR_SUCCESS {
             return None;
         }
 
         let mut buffer = vec![0u8; buf_size as usize];
         let status = RegQueryValueExW(
-            hkey,
-            PCWSTR::from_raw(value_wide.as_ptr()),
+            key,
+            PCWSTR::from_raw(name_wide.as_ptr()),
             None,
             Some(&mut value_type),
             Some(buffer.as_mut_ptr()),
             Some(&mut buf_size),
         );
-        let _ = RegCloseKey(hkey);
-
-        if status == ERROR_SUCCESS && (value_type == REG_SZ || value_type == REG_EXPAND_SZ) {
-            let wide: Vec<u16> = buffer
-                .chunks_exact(2)
-                .map(|c| u16::from_le_bytes([c[0], c[1]]))
-                .collect();
-            Some(String::from_utf16_lossy(&wide).trim_end_matches('\0').to_string())
+        if status != ERROR_SUCCESS {
+            return None;
+        }
+        buffer.truncate(buf_size as usize);
+
+        if value_type == REG_SZ || value_type == REG_EXPAND_SZ {
+            Some(RegValue::Sz(decode_utf16(&buffer).trim_end_matches('\0').to_string()))
+        } else if value_type == REG_MULTI_SZ {
+            Some(RegValue::MultiSz(
+                decode_utf16(&buffer)
+                    .split('\0')
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect(),
+            ))
+        } else if value_type == REG_DWORD && buffer.len() >= 4 {
+            Some(RegValue::Dword(u32::from_le_bytes([
+                buffer[0], buffer[1], buffer[2], buffer[3],
+            ])))
+        } else if value_type == REG_QWORD && buffer.len() >= 8 {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&buffer[..8]);
+            Some(RegValue::Qword(u64::from_le_bytes(bytes)))
         } else {
             None
         }
     }
 }
 
-/// Detect Click-to-Run Office installation
 #[cfg(windows)]
-pub fn find_click_to_run() -> Option<ClickToRunInfo> {
-    use windows::Win32::System::Registry::*;
+fn encode_utf16(s: &str) -> Vec<u8> {
+    s.encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}
+
+#[cfg(windows)]
+fn decode_utf16(buffer: &[u8]) -> String {
+    let wide: Vec<u16> = buffer
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16_lossy(&wide)
+}
+
+#[cfg(windows)]
+impl RegistryReader for Win32Registry {
+    fn open_key(&self, hive: Hive, path: &str) -> bool {
+        use windows::Win32::System::Registry::RegCloseKey;
 
-    let c2r_key = "SOFTWARE\\Microsoft\\Office\\ClickToRun\\Configuration";
+        unsafe {
+            match Self::open(hive, path) {
+                Some(key) => {
+                    let _ = RegCloseKey(key);
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+
+    fn read_value(&self, hive: Hive, path: &str, name: &str) -> Option<RegValue> {
+        use windows::Win32::System::Registry::RegCloseKey;
+
+        unsafe {
+            let key = Self::open(hive, path)?;
+            let value = Self::query_value(key, name);
+            let _ = RegCloseKey(key);
+            value
+        }
+    }
 
-    let install_path = read_reg_string(HKEY_LOCAL_MACHINE, c2r_key, "InstallationPath")?;
-    let product_ids = read_reg_string(HKEY_LOCAL_MACHINE, c2r_key, "ProductReleaseIds")
+    fn subkeys(&self, hive: Hive, path: &str) -> Vec<String> {
+        use windows::Win32::Foundation::*;
+        use windows::Win32::System::Registry::*;
+        use windows::core::*;
+
+        let mut names = Vec::new();
+        unsafe {
+            let key = match Self::open(hive, path) {
+                Some(k) => k,
+                None => return names,
+            };
+
+            let mut index = 0u32;
+            let mut name_buf = vec![0u16; 512];
+            loop {
+                let mut name_len = name_buf.len() as u32;
+                let result = RegEnumKeyExW(
+                    key,
+                    index,
+                    PWSTR::from_raw(name_buf.as_mut_ptr()),
+                    &mut name_len,
+                    None,
+                    PWSTR::null(),
+                    None,
+                    None,
+                );
+                if result != ERROR_SUCCESS {
+                    break;
+                }
+                names.push(String::from_utf16_lossy(&name_buf[..name_len as usize]));
+                index += 1;
+            }
+
+            let _ = RegCloseKey(key);
+        }
+        names
+    }
+}
+
+#[cfg(windows)]
+impl RegistryWriter for Win32Registry {
+    fn write_value(&mut self, hive: Hive, path: &str, name: &str, value: &RegValue) -> Result<(), String> {
+        use windows::Win32::Foundation::*;
+        use windows::Win32::System::Registry::*;
+        use windows::core::*;
+
+        let root = match hive {
+            Hive::LocalMachine => HKEY_LOCAL_MACHINE,
+            Hive::CurrentUser => HKEY_CURRENT_USER,
+        };
+        let (value_type, data) = match value {
+            RegValue::Sz(s) => (REG_SZ, encode_utf16(s)),
+            RegValue::Dword(v) => (REG_DWORD, v.to_le_bytes().to_vec()),
+            RegValue::Qword(v) => (REG_QWORD, v.to_le_bytes().to_vec()),
+            RegValue::MultiSz(items) => {
+                let mut data: Vec<u8> = items.iter().flat_map(|s| encode_utf16(s)).collect();
+                data.extend_from_slice(&[0, 0]);
+                (REG_MULTI_SZ, data)
+            }
+        };
+        let path_wide: Vec<u16> = path.encode_utf16().chain(std::iter::once(0)).collect();
+        let name_wide: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+
+        unsafe {
+            let mut key = HKEY::default();
+            let status = RegCreateKeyExW(
+                root,
+                PCWSTR::from_raw(path_wide.as_ptr()),
+                0,
+                PCWSTR::null(),
+                REG_OPTION_NON_VOLATILE,
+                KEY_SET_VALUE,
+                None,
+                &mut key,
+                None,
+            );
+            if status != ERROR_SUCCESS {
+                return Err(format!("cannot open {}\\{} for writing: {:?}", hive.prefix(), path, status));
+            }
+            let status = RegSetValueExW(
+                key,
+                PCWSTR::from_raw(name_wide.as_ptr()),
+                0,
+                value_type,
+                Some(&data),
+            );
+            let _ = RegCloseKey(key);
+            if status != ERROR_SUCCESS {
+                return Err(format!("cannot write {}: {:?}", name, status));
+            }
+        }
+        Ok(())
+    }
+
+    fn delete_value(&mut self, hive: Hive, path: &str, name: &str) -> Result<(), String> {
+        use windows::Win32::Foundation::*;
+        use windows::Win32::System::Registry::*;
+        use windows::core::*;
+
+        let root = match hive {
+            Hive::LocalMachine => HKEY_LOCAL_MACHINE,
+            Hive::CurrentUser => HKEY_CURRENT_USER,
+        };
+        let path_wide: Vec<u16> = path.encode_utf16().chain(std::iter::once(0)).collect();
+        let name_wide: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+
+        unsafe {
+            let mut key = HKEY::default();
+            let status = RegOpenKeyExW(
+                root,
+                PCWSTR::from_raw(path_wide.as_ptr()),
+                0,
+                KEY_SET_VALUE,
+                &mut key,
+            );
+            if status == ERROR_FILE_NOT_FOUND {
+                return Ok(());
+            }
+            if status != ERROR_SUCCESS {
+                return Err(format!("cannot open {}\\{} for writing: {:?}", hive.prefix(), path, status));
+            }
+            let status = RegDeleteValueW(key, PCWSTR::from_raw(name_wide.as_ptr()));
+            let _ = RegCloseKey(key);
+            if status != ERROR_SUCCESS && status != ERROR_FILE_NOT_FOUND {
+                return Err(format!("cannot delete {}: {:?}", name, status));
+            }
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// In-memory backend
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+struct FakeKey {
+    hive: Hive,
+    /// Path as inserted, without the hive prefix.
+    path: String,
+    /// Values keyed by lowercased name, keeping the original name alongside.
+    values: HashMap<String, (String, RegValue)>,
+}
+
+/// `HashMap`-backed registry used for fixture hives and snapshot replay. Key
+/// paths and value names are matched case-insensitively, like the real registry.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<KeyRecord>", into = "Vec<KeyRecord>")]
+pub struct FakeRegistry {
+    keys: HashMap<String, FakeKey>,
+}
+
+/// Serialized form of a single [`FakeRegistry`] key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyRecord {
+    pub hive: Hive,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub values: BTreeMap<String, RegValue>,
+}
+
+impl FakeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create `path` and all of its parent keys.
+    pub fn insert_key(&mut self, hive: Hive, path: &str) {
+        let mut partial = String::new();
+        for part in path.split('\\').filter(|p| !p.is_empty()) {
+            if !partial.is_empty() {
+                partial.push('\\');
+            }
+            partial.push_str(part);
+            self.keys
+                .entry(key_id(hive, &partial))
+                .or_insert_with(|| FakeKey {
+                    hive,
+                    path: partial.clone(),
+                    values: HashMap::new(),
+                });
+        }
+    }
+
+    pub fn insert_value(&mut self, hive: Hive, path: &str, name: &str, value: RegValue) {
+        self.insert_key(hive, path);
+        if let Some(key) = self.keys.get_mut(&key_id(hive, path)) {
+            key.values
+                .insert(name.to_lowercase(), (name.to_string(), value));
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_key(mut self, hive: Hive, path: &str) -> Self {
+        self.insert_key(hive, path);
+        self
+    }
+
+    #[cfg(test)]
+    pub fn with_value(mut self, hive: Hive, path: &str, name: &str, value: RegValue) -> Self {
+        self.insert_value(hive, path, name, value);
+        self
+    }
+}
+
+fn key_id(hive: Hive, path: &str) -> String {
+    let mut id = hive.prefix().to_lowercase();
+    for part in path.split('\\').filter(|p| !p.is_empty()) {
+        id.push('\\');
+        id.push_str(&part.to_lowercase());
+    }
+    id
+}
+
+impl From<Vec<KeyRecord>> for FakeRegistry {
+    fn from(records: Vec<KeyRecord>) -> Self {
+        let mut reg = FakeRegistry::new();
+        for record in records {
+            reg.insert_key(record.hive, &record.path);
+            for (name, value) in record.values {
+                reg.insert_value(record.hive, &record.path, &name, value);
+            }
+        }
+        reg
+    }
+}
+
+impl From<FakeRegistry> for Vec<KeyRecord> {
+    /// Parent keys without values are implied by their children and left out.
+    fn from(reg: FakeRegistry) -> Self {
+        let mut records: Vec<(String, KeyRecord)> = reg
+            .keys
+            .iter()
+            .filter(|(id, key)| {
+                let child_prefix = format!("{}\\", id);
+                !key.values.is_empty() || !reg.keys.keys().any(|other| other.starts_with(&child_prefix))
+            })
+            .map(|(id, key)| {
+                let record = KeyRecord {
+                    hive: key.hive,
+                    path: key.path.clone(),
+                    values: key.values.values().cloned().collect(),
+                };
+                (id.clone(), record)
+            })
+            .collect();
+        records.sort_by(|a, b| a.0.cmp(&b.0));
+        records.into_iter().map(|(_, record)| record).collect()
+    }
+}
+
+impl RegistryWriter for FakeRegistry {
+    fn write_value(&mut self, hive: Hive, path: &str, name: &str, value: &RegValue) -> Result<(), String> {
+        self.insert_value(hive, path, name, value.clone());
+        Ok(())
+    }
+
+    fn delete_value(&mut self, hive: Hive, path: &str, name: &str) -> Result<(), String> {
+        if let Some(key) = self.keys.get_mut(&key_id(hive, path)) {
+            key.values.remove(&name.to_lowercase());
+        }
+        Ok(())
+    }
+}
+
+impl RegistryReader for FakeRegistry {
+    fn open_key(&self, hive: Hive, path: &str) -> bool {
+        self.keys.contains_key(&key_id(hive, path))
+    }
+
+    fn read_value(&self, hive: Hive, path: &str, name: &str) -> Option<RegValue> {
+        self.keys
+            .get(&key_id(hive, path))?
+            .values
+            .get(&name.to_lowercase())
+            .map(|(_, value)| value.clone())
+    }
+
+    fn subkeys(&self, hive: Hive, path: &str) -> Vec<String> {
+        let prefix = format!("{}\\", key_id(hive, path));
+        let mut names: Vec<String> = self
+            .keys
+            .iter()
+            .filter(|(id, _)| id.starts_with(&prefix) && !id[prefix.len()..].contains('\\'))
+            .filter_map(|(_, key)| key.path.rsplit('\\').next().map(String::from))
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Office detection
+// ---------------------------------------------------------------------------
+
+/// The `InstallRoot` key holding the install path of `app` for one Office
+/// version, and that path.
+pub fn find_office_install_root(
+    reg: &dyn RegistryReader,
+    app: &str,
+    version: &str,
+) -> Option<(String, String)> {
+    let paths = [
+        format!(
+            "SOFTWARE\\Microsoft\\Office\\{}\\{}\\InstallRoot",
+            version, app
+        ),
+        format!(
+            "SOFTWARE\\WOW6432Node\\Microsoft\\Office\\{}\\{}\\InstallRoot",
+            version, app
+        ),
+    ];
+
+    paths.into_iter().find_map(|key| {
+        let path = reg.read_string(Hive::LocalMachine, &key, "Path")?;
+        Some((key, path))
+    })
+}
+
+/// Office versions with a desktop OneNote/Word that the exporter supports,
+/// newest first. A policy file may replace this list.
+pub const OFFICE_VERSIONS: [&str; 2] = ["16.0", "15.0"];
+
+pub const OFFICE_URL: &str = "https://www.office.com";
+
+/// Catalog keys of the Settings path to Office's Online Repair.
+pub const OFFICE_REPAIR_MENU: &[&str] = &[
+    "menu.settings",
+    "menu.apps",
+    "menu.microsoft_office",
+    "menu.modify",
+    "menu.online_repair",
+];
+
+/// An MSI-style Office app install found under `InstallRoot`.
+#[derive(Debug, Clone)]
+pub struct OfficeInstall {
+    pub version: String,
+    pub path: String,
+    /// The `HKLM` `InstallRoot` key `path` was read from.
+    pub key: String,
+}
+
+impl OfficeInstall {
+    pub fn evidence(&self) -> Evidence {
+        Hive::LocalMachine.evidence(&self.key, "Path", self.path.as_str())
+    }
+}
+
+/// Find the first of `versions` that has `app` installed.
+pub fn detect_office_install(
+    reg: &dyn RegistryReader,
+    app: &str,
+    versions: &[String],
+) -> Option<OfficeInstall> {
+    versions.iter().find_map(|version| {
+        find_office_install_root(reg, app, version).map(|(key, path)| OfficeInstall {
+            version: version.clone(),
+            path,
+            key,
+        })
+    })
+}
+
+/// Info about a Click-to-Run Office installation
+pub struct ClickToRunInfo {
+    pub install_path: String,
+    pub product_ids: String,
+    pub version: Option<String>,
+}
+
+const C2R_CONFIGURATION_KEY: &str = "SOFTWARE\\Microsoft\\Office\\ClickToRun\\Configuration";
+
+/// Detect Click-to-Run Office installation
+pub fn find_click_to_run(reg: &dyn RegistryReader) -> Option<ClickToRunInfo> {
+    let install_path =
+        reg.read_string(Hive::LocalMachine, C2R_CONFIGURATION_KEY, "InstallationPath")?;
+    let product_ids = reg
+        .read_string(Hive::LocalMachine, C2R_CONFIGURATION_KEY, "ProductReleaseIds")
         .unwrap_or_default();
-    let version = read_reg_string(HKEY_LOCAL_MACHINE, c2r_key, "VersionToReport");
+    let version = reg.read_string(Hive::LocalMachine, C2R_CONFIGURATION_KEY, "VersionToReport");
 
     Some(ClickToRunInfo {
         install_path,
@@ -212,26 +621,36 @@ pub fn find_click_to_run() -> Option<ClickToRunInfo> {
     })
 }
 
-#[cfg(not(windows))]
-pub fn find_click_to_run() -> Option<ClickToRunInfo> {
-    None
+impl ClickToRunInfo {
+    pub fn evidence(&self) -> Vec<Evidence> {
+        let mut evidence = vec![Hive::LocalMachine.evidence(
+            C2R_CONFIGURATION_KEY,
+            "ProductReleaseIds",
+            self.product_ids.as_str(),
+        )];
+        if let Some(version) = &self.version {
+            evidence.push(Hive::LocalMachine.evidence(
+                C2R_CONFIGURATION_KEY,
+                "VersionToReport",
+                version.as_str(),
+            ));
+        }
+        evidence
+    }
 }
 
 /// Check if a specific app is excluded from a C2R installation.
 /// Checks ExcludedApps for each product ID (e.g. "O365ProPlusRetail.ExcludedApps").
-#[cfg(windows)]
-pub fn is_c2r_app_excluded(product_ids: &str, app_name: &str) -> bool {
-    use windows::Win32::System::Registry::*;
-
-    let c2r_key = "SOFTWARE\\Microsoft\\Office\\ClickToRun\\Configuration";
-
+pub fn is_c2r_app_excluded(reg: &dyn RegistryReader, product_ids: &str, app_name: &str) -> bool {
     for product_id in product_ids.split(',') {
         let product_id = product_id.trim();
         if product_id.is_empty() {
             continue;
         }
         let value_name = format!("{}.ExcludedApps", product_id);
-        if let Some(excluded) = read_reg_string(HKEY_LOCAL_MACHINE, c2r_key, &value_name) {
+        if let Some(excluded) =
+            reg.read_string(Hive::LocalMachine, C2R_CONFIGURATION_KEY, &value_name)
+        {
             if excluded.to_lowercase().contains(&app_name.to_lowercase()) {
                 return true;
             }
@@ -241,12 +660,14 @@ pub fn is_c2r_app_excluded(product_ids: &str, app_name: &str) -> bool {
     false
 }
 
-#[cfg(not(windows))]
-pub fn is_c2r_app_excluded(_product_ids: &str, _app_name: &str) -> bool {
-    false
-}
+// ---------------------------------------------------------------------------
+// COM activation
+// ---------------------------------------------------------------------------
 
 /// Test COM activation for an Office app. Returns Ok(()) if COM works.
+///
+/// Enters and leaves a single-threaded apartment on the calling thread, so it
+/// is safe to call from several check threads at once.
 #[cfg(windows)]
 pub fn test_com_activation(prog_id: &str) -> Result<(), String> {
     use windows::Win32::System::Com::*;
@@ -267,19 +688,83 @@ pub fn test_com_activation(prog_id: &str) -> Result<(), String> {
             }
         };
 
-        let result: windows::core::Result<IUnknown> =
-            CoCreateInstance(&clsid, None, CLSCTX_LOCAL_SERVER);
+        // Release the instance before leaving the apartment.
+        let outcome = match CoCreateInstance::<_, IUnknown>(&clsid, None, CLSCTX_LOCAL_SERVER) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(format!("CoCreateInstance: {}", e)),
+        };
 
         CoUninitialize();
+        outcome
+    }
+}
 
-        match result {
-            Ok(_) => Ok(()),
-            Err(e) => Err(format!("CoCreateInstance: {}", e)),
+#[cfg(not(windows))]
+pub fn test_com_activation(_prog_id: &str) -> Result<(), String> {
+    Err("Not on Windows".into())
+}
+
+/// XML of the notebooks OneNote has open, down to page level, from
+/// `IApplication::GetHierarchy`. Called through `IDispatch`, so it does not
+/// depend on the interface id of a particular OneNote version.
+///
+/// OneNote is started if it is not running, which can take a while.
+#[cfg(windows)]
+pub fn read_onenote_hierarchy() -> Result<String, String> {
+    use windows::Win32::System::Com::*;
+
+    unsafe {
+        let hr = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        if hr.is_err() {
+            return Err(format!("COM init failed: {:?}", hr));
         }
+        // Releases the instance before we leave the apartment.
+        let outcome = invoke_get_hierarchy();
+        CoUninitialize();
+        outcome
     }
 }
 
+#[cfg(windows)]
+unsafe fn invoke_get_hierarchy() -> Result<String, String> {
+    use windows::core::*;
+    use windows::Win32::Globalization::LOCALE_USER_DEFAULT;
+    use windows::Win32::System::Com::*;
+    use windows::Win32::System::Variant::{VT_BSTR, VT_BYREF};
+
+    /// `HierarchyScope.hsPages`: notebooks, section groups, sections and pages.
+    const HS_PAGES: i32 = 4;
+
+    let prog_id: HSTRING = "OneNote.Application".into();
+    let clsid = CLSIDFromProgID(&prog_id).map_err(|e| format!("CLSIDFromProgID: {}", e))?;
+    let app: IDispatch = CoCreateInstance(&clsid, None, CLSCTX_LOCAL_SERVER)
+        .map_err(|e| format!("CoCreateInstance: {}", e))?;
+
+    let name = w!("GetHierarchy");
+    let mut dispid = 0;
+    app.GetIDsOfNames(&GUID::zeroed(), &name, 1, LOCALE_USER_DEFAULT, &mut dispid)
+        .map_err(|e| format!("GetIDsOfNames: {}", e))?;
+
+    // The XML comes back through a `BSTR*` out parameter.
+    let mut xml = BSTR::new();
+    let mut out: imp::VARIANT = std::mem::zeroed();
+    out.Anonymous.Anonymous.vt = VT_BYREF.0 | VT_BSTR.0;
+    out.Anonymous.Anonymous.Anonymous.pbstrVal = &mut xml as *mut BSTR as *mut imp::BSTR;
+    // IDispatch takes the arguments last to first.
+    let mut args = [VARIANT::from_raw(out), VARIANT::from(HS_PAGES), VARIANT::from("")];
+    let params = DISPPARAMS {
+        rgvarg: args.as_mut_ptr(),
+        rgdispidNamedArgs: std::ptr::null_mut(),
+        cArgs: args.len() as u32,
+        cNamedArgs: 0,
+    };
+    app.Invoke(dispid, &GUID::zeroed(), LOCALE_USER_DEFAULT, DISPATCH_METHOD, &params, None, None, None)
+        .map_err(|e| format!("GetHierarchy: {}", e))?;
+    drop(args);
+    Ok(xml.to_string())
+}
+
 #[cfg(not(windows))]
-pub fn test_com_activation(_prog_id: &str) -> Result<(), String> {
+pub fn read_onenote_hierarchy() -> Result<String, String> {
     Err("Not on Windows".into())
 }
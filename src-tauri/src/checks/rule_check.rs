@@ -0,0 +1,189 @@
+use crate::types::{CheckCategory, CheckResult, CheckStatus, RemediationStep, Severity};
+
+use super::policy::RegistryRule;
+use super::registry::RegValue;
+use super::{Check, ScanContext};
+
+/// A check defined in the policy file rather than in code.
+pub struct RegistryRuleCheck {
+    rule: RegistryRule,
+}
+
+impl RegistryRuleCheck {
+    pub fn new(rule: RegistryRule) -> Self {
+        Self { rule }
+    }
+}
+
+impl Check for RegistryRuleCheck {
+    fn id(&self) -> &str {
+        &self.rule.id
+    }
+
+    fn label(&self) -> &str {
+        &self.rule.label
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Policy
+    }
+
+    fn severity(&self) -> Severity {
+        self.rule.severity
+    }
+
+    fn run(&self, ctx: &ScanContext) -> CheckResult {
+        if !ctx.is_supported() {
+            return CheckResult {
+                id: self.rule.id.clone(),
+                label: self.rule.label.clone(),
+                status: CheckStatus::Fail,
+                message: ctx.t("rule.unsupported", &[]),
+                remediation: vec![ctx.locale().instruction("common.windows_required", &[])],
+                ..Default::default()
+            };
+        }
+        check(&self.rule, ctx)
+    }
+}
+
+pub fn check(rule: &RegistryRule, ctx: &ScanContext) -> CheckResult {
+    let location = format!("{}\\{}!{}", rule.hive.prefix(), rule.path, rule.name);
+    let actual = ctx.registry().read_value(rule.hive, &rule.path, &rule.name);
+    let evidence = vec![rule.hive.evidence(
+        &rule.path,
+        &rule.name,
+        actual.as_ref().map_or_else(|| "(not set)".into(), display_value),
+    )];
+
+    if actual.as_ref().is_some_and(|v| rule.equals.matches(v)) {
+        return CheckResult {
+            id: rule.id.clone(),
+            label: rule.label.clone(),
+            status: CheckStatus::Pass,
+            message: ctx.t("rule.pass", &[("location", &location), ("expected", &rule.equals)]),
+            remediation: Vec::new(),
+            evidence,
+            ..Default::default()
+        };
+    }
+
+    let found = match &actual {
+        Some(v) => ctx.t("rule.found", &[("value", &display_value(v))]),
+        None => ctx.t("rule.not_set", &[]),
+    };
+    let status = match rule.severity {
+        Severity::Blocker | Severity::Major => CheckStatus::Fail,
+        Severity::Minor | Severity::Info => CheckStatus::Warning,
+    };
+    CheckResult {
+        id: rule.id.clone(),
+        label: rule.label.clone(),
+        status,
+        message: ctx.t(
+            "rule.violated",
+            &[("location", &location), ("found", &found), ("expected", &rule.equals)],
+        ),
+        remediation: vec![match &rule.remediation {
+            Some(text) => RemediationStep::instruction(text.as_str()),
+            None => ctx
+                .locale()
+                .instruction("rule.remediation", &[("location", &location), ("expected", &rule.equals)]),
+        }],
+        evidence,
+        ..Default::default()
+    }
+}
+
+fn display_value(value: &RegValue) -> String {
+    match value {
+        RegValue::Sz(s) => format!("\"{}\"", s),
+        RegValue::Dword(v) => v.to_string(),
+        RegValue::Qword(v) => v.to_string(),
+        RegValue::MultiSz(items) => items.join("; "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::fixtures::context;
+    use crate::checks::host::FakeHost;
+    use crate::checks::policy::RuleValue;
+    use crate::checks::registry::{FakeRegistry, Hive};
+
+    const PATH: &str = "Software\\Microsoft\\Office\\16.0\\OneNote\\Options";
+
+    fn rule(severity: Severity, remediation: Option<&str>) -> RegistryRule {
+        RegistryRule {
+            id: "onenote_no_cloud_defaults".into(),
+            label: "OneNote saves locally".into(),
+            hive: Hive::CurrentUser,
+            path: PATH.into(),
+            name: "DefaultSaveToCloud".into(),
+            equals: RuleValue::Number(0),
+            severity,
+            remediation: remediation.map(str::to_string),
+        }
+    }
+
+    fn run(rule: &RegistryRule, value: Option<RegValue>) -> CheckResult {
+        let mut registry = FakeRegistry::new();
+        if let Some(value) = value {
+            registry = registry.with_value(Hive::CurrentUser, PATH, "DefaultSaveToCloud", value);
+        }
+        check(rule, &context(registry, FakeHost::new()))
+    }
+
+    #[test]
+    fn passes_when_the_value_matches() {
+        let result = run(&rule(Severity::Major, None), Some(RegValue::Dword(0)));
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert_eq!(result.message, format!("HKCU\\{}!DefaultSaveToCloud is 0, as required by policy.", PATH));
+        assert_eq!(result.evidence[0].value, "0");
+        assert!(result.remediation.is_empty());
+    }
+
+    #[test]
+    fn blocker_and_major_rules_fail() {
+        for severity in [Severity::Blocker, Severity::Major] {
+            let result = run(&rule(severity, None), Some(RegValue::Dword(1)));
+            assert_eq!(result.status, CheckStatus::Fail, "{:?}", severity);
+            assert!(result.message.contains("DefaultSaveToCloud is 1, but policy requires 0"), "{}", result.message);
+        }
+    }
+
+    #[test]
+    fn minor_and_info_rules_warn() {
+        for severity in [Severity::Minor, Severity::Info] {
+            let result = run(&rule(severity, None), None);
+            assert_eq!(result.status, CheckStatus::Warning, "{:?}", severity);
+            assert!(result.message.contains("DefaultSaveToCloud is not set, but"), "{}", result.message);
+            assert_eq!(result.evidence[0].value, "(not set)");
+        }
+    }
+
+    #[test]
+    fn the_policy_remediation_replaces_the_generic_one() {
+        let text = "Contact the service desk to apply the OneNote baseline.";
+        let result = run(&rule(Severity::Minor, Some(text)), Some(RegValue::Dword(1)));
+        assert_eq!(result.remediation, [RemediationStep::instruction(text)]);
+
+        let result = run(&rule(Severity::Minor, None), Some(RegValue::Dword(1)));
+        assert_eq!(
+            result.remediation,
+            [RemediationStep::instruction(format!(
+                "Set HKCU\\{}!DefaultSaveToCloud to 0, or ask your IT department to apply the setting.",
+                PATH
+            ))]
+        );
+    }
+
+    #[test]
+    fn text_values_compare_without_case() {
+        let mut rule = rule(Severity::Major, None);
+        rule.equals = RuleValue::Text("Local".into());
+        assert_eq!(run(&rule, Some(RegValue::Sz("LOCAL".into()))).status, CheckStatus::Pass);
+        assert_eq!(run(&rule, Some(RegValue::Dword(0))).status, CheckStatus::Fail);
+    }
+}
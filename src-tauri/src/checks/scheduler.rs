@@ -0,0 +1,445 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::i18n::Locale;
+use crate::types::{CheckResult, CheckStatus, RemediationStep, ScanError};
+
+use super::registry::OFFICE_REPAIR_MENU;
+use super::{Check, ScanContext, ScanObserver};
+
+/// How often a waiting scan looks at its cancellation flag.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Shared flag used to stop a scan in flight.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Indices of `checks` ordered so that every check comes after the checks it
+/// depends on. Independent checks keep their registration order.
+pub fn dependency_order(checks: &[Arc<dyn Check>]) -> Result<Vec<usize>, ScanError> {
+    let index_of = |id: &str| checks.iter().position(|c| c.id() == id);
+    for check in checks {
+        if let Some(missing) = check.depends_on().iter().find(|d| index_of(d).is_none()) {
+            return Err(ScanError::Unexpected(format!(
+                "Check '{}' depends on unknown check '{}'",
+                check.id(),
+                missing
+            )));
+        }
+    }
+
+    let mut placed = vec![false; checks.len()];
+    let mut order = Vec::with_capacity(checks.len());
+    while order.len() < checks.len() {
+        let next = (0..checks.len()).find(|&i| {
+            !placed[i]
+                && checks[i]
+                    .depends_on()
+                    .iter()
+                    .all(|d| index_of(d).is_some_and(|j| placed[j]))
+        });
+        match next {
+            Some(i) => {
+                placed[i] = true;
+                order.push(i);
+            }
+            None => {
+                return Err(ScanError::Unexpected(
+                    "Check dependencies contain a cycle".into(),
+                ))
+            }
+        }
+    }
+    Ok(order)
+}
+
+/// The `Skipped` result to report for `check` if one of its prerequisites did
+/// not pass or warn. `results` is indexed like `checks`.
+pub fn unmet_prerequisite(
+    check: &dyn Check,
+    checks: &[Arc<dyn Check>],
+    results: &[Option<CheckResult>],
+    locale: Locale,
+) -> Option<CheckResult> {
+    check.depends_on().iter().find_map(|dep| {
+        let index = checks.iter().position(|c| c.id() == *dep)?;
+        let key = match results[index].as_ref()?.status {
+            CheckStatus::Pass | CheckStatus::Warning => return None,
+            CheckStatus::Fail => "scheduler.prerequisite_failed",
+            CheckStatus::Skipped => "scheduler.prerequisite_skipped",
+            CheckStatus::Timeout => "scheduler.prerequisite_timed_out",
+        };
+        Some(CheckResult {
+            id: check.id().into(),
+            label: check.label().into(),
+            status: CheckStatus::Skipped,
+            message: locale.t(key, &[("prerequisite", &locale.check_label(checks[index].as_ref()))]),
+            remediation: Vec::new(),
+            ..Default::default()
+        })
+    })
+}
+
+/// Run every check in `checks`, each on its own thread, starting a check as
+/// soon as all of its prerequisites have finished. Independent checks, such
+/// as the slow COM probes and the registry-only checks, therefore run at the
+/// same time. Results are indexed like `checks`, whatever order they finish in.
+///
+/// A check that does not finish within the context's timeout is reported as
+/// [`CheckStatus::Timeout`]. Its thread cannot be stopped from the outside
+/// (e.g. a hung `CoCreateInstance`), so it is left to finish in the background
+/// and its late result is discarded. Cancelling the context's token stops the
+/// scan with [`ScanError::Cancelled`].
+pub fn run_parallel(
+    checks: &[Arc<dyn Check>],
+    ctx: &Arc<ScanContext>,
+    observer: &dyn ScanObserver,
+) -> Result<Vec<CheckResult>, ScanError> {
+    let order = dependency_order(checks)?;
+    let mut results: Vec<Option<CheckResult>> = vec![None; checks.len()];
+    let mut started = vec![false; checks.len()];
+    let mut deadlines: Vec<(usize, Instant)> = Vec::new();
+    let (tx, rx) = mpsc::channel();
+
+    loop {
+        // Start everything whose prerequisites are done, in dependency order
+        // so that skips cascade within a single pass.
+        for &index in &order {
+            let check = &checks[index];
+            let ready = check.depends_on().iter().all(|dep| {
+                checks
+                    .iter()
+                    .position(|c| c.id() == *dep)
+                    .is_some_and(|j| results[j].is_some())
+            });
+            if started[index] || !ready {
+                continue;
+            }
+            started[index] = true;
+            observer.check_started(&ctx.locale().check_info(check.as_ref()));
+            match unmet_prerequisite(check.as_ref(), checks, &results, ctx.locale()) {
+                Some(skipped) => {
+                    results[index] = Some(finish(check.as_ref(), skipped, ctx, observer));
+                }
+                None => {
+                    spawn_check(index, check, ctx, tx.clone())?;
+                    deadlines.push((index, Instant::now() + ctx.timeout()));
+                }
+            }
+        }
+
+        if deadlines.is_empty() {
+            break;
+        }
+        if ctx.cancel_token().is_cancelled() {
+            return Err(ScanError::Cancelled);
+        }
+
+        let now = Instant::now();
+        let expired: Vec<usize> = deadlines
+            .iter()
+            .filter(|(_, deadline)| *deadline <= now)
+            .map(|(index, _)| *index)
+            .collect();
+        if !expired.is_empty() {
+            for index in expired {
+                let check = checks[index].as_ref();
+                let result = timed_out(check, ctx);
+                results[index] = Some(finish(check, result, ctx, observer));
+            }
+            deadlines.retain(|(index, _)| results[*index].is_none());
+            continue;
+        }
+
+        let next_deadline = deadlines.iter().map(|(_, d)| *d).min().unwrap_or(now);
+        match rx.recv_timeout(POLL_INTERVAL.min(next_deadline - now)) {
+            Ok((index, result)) => {
+                // Late results of checks that already timed out are dropped.
+                if results[index].is_none() {
+                    results[index] = Some(finish(checks[index].as_ref(), result, ctx, observer));
+                    deadlines.retain(|(i, _)| *i != index);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            // `tx` is still held here, so the channel cannot disconnect.
+            Err(RecvTimeoutError::Disconnected) => unreachable!(),
+        }
+    }
+
+    Ok(results.into_iter().flatten().collect())
+}
+
+/// Stamp the check's translated label and scoring metadata onto `result` and
+/// report it.
+fn finish(
+    check: &dyn Check,
+    mut result: CheckResult,
+    ctx: &ScanContext,
+    observer: &dyn ScanObserver,
+) -> CheckResult {
+    result.label = ctx.locale().check_label(check);
+    result.severity = check.severity();
+    result.weight = check.weight();
+    observer.check_finished(&result);
+    result
+}
+
+/// Run `check` on a fresh thread and send its result, tagged with `index`.
+/// COM probes initialise their own apartment on this thread, so concurrent
+/// COM checks never share one.
+fn spawn_check(
+    index: usize,
+    check: &Arc<dyn Check>,
+    ctx: &Arc<ScanContext>,
+    tx: Sender<(usize, CheckResult)>,
+) -> Result<(), ScanError> {
+    let thread_check = Arc::clone(check);
+    let thread_ctx = Arc::clone(ctx);
+    std::thread::Builder::new()
+        .name(format!("check-{}", check.id()))
+        .spawn(move || {
+            let started = Instant::now();
+            let mut result =
+                panic::catch_unwind(AssertUnwindSafe(|| thread_check.run(&thread_ctx)))
+                    .unwrap_or_else(|_| crashed(thread_check.as_ref(), thread_ctx.locale()));
+            result.duration_ms = started.elapsed().as_millis() as u64;
+            if matches!(result.status, CheckStatus::Fail | CheckStatus::Warning) {
+                if let Some(fix) = thread_check.fix(&thread_ctx) {
+                    result.remediation.push(RemediationStep::AutoFix {
+                        check_id: thread_check.id().into(),
+                        description: fix.description,
+                    });
+                }
+            }
+            let _ = tx.send((index, result));
+        })
+        .map(|_| ())
+        .map_err(|e| ScanError::Unexpected(format!("Failed to start check thread: {}", e)))
+}
+
+fn timed_out(check: &dyn Check, ctx: &ScanContext) -> CheckResult {
+    let locale = ctx.locale();
+    CheckResult {
+        id: check.id().into(),
+        label: check.label().into(),
+        status: CheckStatus::Timeout,
+        message: locale.t("scheduler.timed_out", &[("seconds", &ctx.timeout().as_secs())]),
+        remediation: vec![
+            locale.instruction("scheduler.timed_out.remediation", &[]),
+            locale.menu(OFFICE_REPAIR_MENU),
+        ],
+        evidence: Vec::new(),
+        duration_ms: ctx.timeout().as_millis() as u64,
+        ..Default::default()
+    }
+}
+
+fn crashed(check: &dyn Check, locale: Locale) -> CheckResult {
+    CheckResult {
+        id: check.id().into(),
+        label: check.label().into(),
+        status: CheckStatus::Fail,
+        message: locale.t("scheduler.crashed", &[]),
+        remediation: vec![locale.instruction("scheduler.crashed.remediation", &[])],
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::fixtures::context;
+    use crate::checks::host::FakeHost;
+    use crate::checks::registry::FakeRegistry;
+    use crate::checks::NoopObserver;
+    use crate::types::CheckCategory;
+
+    /// A check that takes `delay` to report `status`, or panics.
+    struct Fake {
+        id: &'static str,
+        depends_on: &'static [&'static str],
+        status: CheckStatus,
+        delay: Duration,
+        panics: bool,
+    }
+
+    impl Check for Fake {
+        fn id(&self) -> &str {
+            self.id
+        }
+
+        fn label(&self) -> &str {
+            self.id
+        }
+
+        fn category(&self) -> CheckCategory {
+            CheckCategory::System
+        }
+
+        fn depends_on(&self) -> &'static [&'static str] {
+            self.depends_on
+        }
+
+        fn run(&self, _ctx: &ScanContext) -> CheckResult {
+            std::thread::sleep(self.delay);
+            if self.panics {
+                panic!("{} fell over", self.id);
+            }
+            CheckResult {
+                id: self.id.into(),
+                label: self.id.into(),
+                status: self.status.clone(),
+                message: format!("{} ran", self.id),
+                ..Default::default()
+            }
+        }
+    }
+
+    fn fake(id: &'static str, delay_ms: u64) -> Fake {
+        Fake {
+            id,
+            depends_on: &[],
+            status: CheckStatus::Pass,
+            delay: Duration::from_millis(delay_ms),
+            panics: false,
+        }
+    }
+
+    fn checks(fakes: Vec<Fake>) -> Vec<Arc<dyn Check>> {
+        fakes.into_iter().map(|f| Arc::new(f) as Arc<dyn Check>).collect()
+    }
+
+    fn ctx(timeout: Duration) -> Arc<ScanContext> {
+        Arc::new(context(FakeRegistry::new(), FakeHost::new()).with_timeout(timeout))
+    }
+
+    #[test]
+    fn a_hung_check_times_out_at_its_deadline() {
+        let started = Instant::now();
+        let checks = checks(vec![fake("hung", 10_000), fake("quick", 0)]);
+        let results = run_parallel(&checks, &ctx(Duration::from_millis(300)), &NoopObserver).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5), "{:?}", started.elapsed());
+        assert_eq!(results[0].status, CheckStatus::Timeout);
+        assert_eq!(results[0].duration_ms, 300);
+        assert_eq!(results[1].status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn cancelling_stops_the_scan() {
+        let cancel = CancelToken::new();
+        let ctx = context(FakeRegistry::new(), FakeHost::new())
+            .with_timeout(Duration::from_secs(60))
+            .with_cancel_token(cancel.clone());
+        let checks = checks(vec![fake("slow", 10_000)]);
+        let started = Instant::now();
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            cancel.cancel();
+        });
+        let outcome = run_parallel(&checks, &Arc::new(ctx), &NoopObserver);
+        canceller.join().unwrap();
+        assert!(matches!(outcome, Err(ScanError::Cancelled)), "{:?}", outcome);
+        assert!(started.elapsed() < Duration::from_secs(5), "{:?}", started.elapsed());
+    }
+
+    #[test]
+    fn a_panicking_check_is_reported_as_crashed() {
+        let checks = checks(vec![
+            Fake {
+                panics: true,
+                ..fake("broken", 0)
+            },
+            fake("fine", 0),
+        ]);
+        let results = run_parallel(&checks, &ctx(Duration::from_secs(5)), &NoopObserver).unwrap();
+        assert_eq!(results[0].status, CheckStatus::Fail);
+        assert_eq!(results[0].message, Locale::En.t("scheduler.crashed", &[]));
+        assert_eq!(results[1].status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn results_are_in_registry_order_whatever_finishes_first() {
+        let checks = checks(vec![fake("slowest", 300), fake("fastest", 0), fake("middle", 100)]);
+        let results = run_parallel(&checks, &ctx(Duration::from_secs(5)), &NoopObserver).unwrap();
+        let ids: Vec<_> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["slowest", "fastest", "middle"]);
+        assert!(results.iter().all(|r| r.status == CheckStatus::Pass));
+    }
+
+    fn after(id: &'static str, depends_on: &'static [&'static str]) -> Fake {
+        Fake {
+            depends_on,
+            ..fake(id, 0)
+        }
+    }
+
+    fn unexpected(outcome: Result<Vec<usize>, ScanError>) -> String {
+        match outcome {
+            Err(ScanError::Unexpected(message)) => message,
+            other => panic!("expected an error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn prerequisites_come_first_and_others_keep_their_order() {
+        let checks = checks(vec![after("word", &["office"]), fake("windows", 0), fake("office", 0)]);
+        assert_eq!(dependency_order(&checks).unwrap(), [1, 2, 0]);
+    }
+
+    #[test]
+    fn a_dependency_cycle_is_an_error() {
+        let checks = checks(vec![after("a", &["b"]), after("b", &["c"]), after("c", &["a"])]);
+        assert_eq!(unexpected(dependency_order(&checks)), "Check dependencies contain a cycle");
+    }
+
+    #[test]
+    fn an_unknown_prerequisite_is_an_error() {
+        let checks = checks(vec![fake("office", 0), after("word", &["office", "offcie"])]);
+        assert_eq!(
+            unexpected(dependency_order(&checks)),
+            "Check 'word' depends on unknown check 'offcie'"
+        );
+    }
+
+    #[test]
+    fn skips_cascade_from_a_failed_prerequisite() {
+        let checks = checks(vec![
+            Fake {
+                status: CheckStatus::Fail,
+                ..fake("suite", 0)
+            },
+            // Would be reported as crashed had it run.
+            Fake {
+                panics: true,
+                ..after("app", &["suite"])
+            },
+            after("addin", &["app"]),
+            after("other", &[]),
+        ]);
+        let results = run_parallel(&checks, &ctx(Duration::from_secs(5)), &NoopObserver).unwrap();
+        let statuses: Vec<_> = results.iter().map(|r| r.status.clone()).collect();
+        assert_eq!(
+            statuses,
+            [CheckStatus::Fail, CheckStatus::Skipped, CheckStatus::Skipped, CheckStatus::Pass]
+        );
+        assert_eq!(results[1].message, "Skipped — requires suite, which failed");
+        assert_eq!(results[2].message, "Skipped — requires app, which was skipped");
+    }
+}
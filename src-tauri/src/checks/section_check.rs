@@ -0,0 +1,197 @@
+use crate::types::{CheckCategory, CheckResult, CheckStatus, Evidence};
+
+use super::{Check, ScanContext};
+
+pub struct LockedSectionsCheck;
+
+impl Check for LockedSectionsCheck {
+    fn id(&self) -> &'static str {
+        "locked_sections"
+    }
+
+    fn label(&self) -> &'static str {
+        "Locked Sections"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Office
+    }
+
+    fn depends_on(&self) -> &'static [&'static str] {
+        &["onenote"]
+    }
+
+    fn run(&self, ctx: &ScanContext) -> CheckResult {
+        check(ctx)
+    }
+}
+
+/// Where OneNote removes a section's password.
+const PASSWORD_MENU: &[&str] = &["menu.onenote", "menu.review", "menu.password"];
+
+/// The exporter cannot open password-protected sections and skips them
+/// without saying so, so they are listed before the migration starts.
+///
+/// This is the only check that reads the notebooks, as `GetHierarchy` starts
+/// OneNote if it is not already running.
+pub fn check(ctx: &ScanContext) -> CheckResult {
+    let inventory = match ctx.onenote_inventory() {
+        Ok(inventory) => inventory,
+        Err(e) => {
+            return CheckResult {
+                id: "locked_sections".into(),
+                label: "Locked Sections".into(),
+                status: CheckStatus::Warning,
+                message: ctx.t("locked_sections.unreadable", &[("error", &e)]),
+                remediation: vec![ctx.locale().instruction("locked_sections.open_notebooks", &[])],
+                evidence: vec![Evidence::new("GetHierarchy", e, "COM")],
+                ..Default::default()
+            };
+        }
+    };
+    if inventory.notebooks.is_empty() {
+        return CheckResult {
+            id: "locked_sections".into(),
+            label: "Locked Sections".into(),
+            status: CheckStatus::Warning,
+            message: ctx.t("locked_sections.no_notebooks", &[]),
+            remediation: vec![ctx.locale().instruction("locked_sections.open_notebooks", &[])],
+            ..Default::default()
+        };
+    }
+
+    let mut evidence = vec![
+        Evidence::new("Notebooks", inventory.notebooks.len().to_string(), "COM"),
+        Evidence::new("Sections", inventory.sections().len().to_string(), "COM"),
+        Evidence::new("Pages", inventory.page_count().to_string(), "COM"),
+    ];
+    let mut listed = Vec::new();
+    let (mut password, mut read_only) = (false, false);
+    for (notebook, section) in inventory.sections() {
+        let flags = [
+            ("encrypted", section.encrypted, "locked_sections.encrypted"),
+            ("isLocked", section.is_locked, "locked_sections.locked"),
+            ("readOnly", section.read_only, "locked_sections.read_only"),
+        ];
+        let mut reasons = Vec::new();
+        for (attribute, set, reason) in flags {
+            if set {
+                evidence.push(Evidence::new(attribute, "true", &section.path));
+                reasons.push(ctx.t(reason, &[]));
+            }
+        }
+        if reasons.is_empty() {
+            continue;
+        }
+        password |= section.is_locked || section.encrypted;
+        read_only |= section.read_only;
+        listed.push(ctx.t(
+            "locked_sections.item",
+            &[
+                ("notebook", &notebook.name),
+                ("section", &section.name),
+                ("reasons", &reasons.join(", ")),
+            ],
+        ));
+    }
+
+    if listed.is_empty() {
+        return CheckResult {
+            id: "locked_sections".into(),
+            label: "Locked Sections".into(),
+            status: CheckStatus::Pass,
+            message: ctx.t("locked_sections.none", &[]),
+            remediation: Vec::new(),
+            evidence,
+            ..Default::default()
+        };
+    }
+
+    let mut remediation = Vec::new();
+    if password {
+        remediation.push(ctx.locale().instruction("locked_sections.unlock", &[]));
+        remediation.push(ctx.locale().menu(PASSWORD_MENU));
+    }
+    if read_only {
+        remediation.push(ctx.locale().instruction("locked_sections.read_only.remediation", &[]));
+    }
+    CheckResult {
+        id: "locked_sections".into(),
+        label: "Locked Sections".into(),
+        status: CheckStatus::Warning,
+        message: ctx.t("locked_sections.found", &[("sections", &listed.join("; "))]),
+        remediation,
+        evidence,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::fixtures::{context, HIERARCHY};
+    use crate::checks::host::FakeHost;
+    use crate::checks::registry::FakeRegistry;
+    use crate::i18n::Locale;
+    use crate::types::RemediationStep;
+
+    fn run(hierarchy: Result<String, String>) -> CheckResult {
+        check(&context(FakeRegistry::new(), FakeHost::new().with_hierarchy(hierarchy)))
+    }
+
+    #[test]
+    fn lists_protected_and_read_only_sections_by_notebook() {
+        let result = run(Ok(HIERARCHY.into()));
+        assert_eq!(result.status, CheckStatus::Warning);
+        assert_eq!(
+            result.message,
+            "These sections are likely to be skipped by the export: \
+             Work: HR (password-protected, locked); \
+             Work: Roadmap (password-protected); \
+             Work: 2022 (read-only)"
+        );
+
+        let counts: Vec<_> = result.evidence[..3]
+            .iter()
+            .map(|e| (e.key.as_str(), e.value.as_str()))
+            .collect();
+        assert_eq!(counts, [("Notebooks", "2"), ("Sections", "5"), ("Pages", "5")]);
+        let flags: Vec<_> = result.evidence[3..].iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(flags, ["encrypted", "isLocked", "encrypted", "readOnly"]);
+        assert!(result.evidence[3].source.ends_with("/Work/HR.one"));
+
+        assert_eq!(result.remediation.len(), 3);
+        assert_eq!(
+            result.remediation[1],
+            RemediationStep::MenuPath {
+                path: vec!["OneNote".into(), "Review".into(), "Password".into()]
+            }
+        );
+    }
+
+    #[test]
+    fn passes_without_protected_sections() {
+        let xml = r#"<Notebooks><Notebook ID="n" name="Notes" path="C:\Notes\"><Section ID="s" name="Inbox" path="C:\Notes\Inbox.one"/></Notebook></Notebooks>"#;
+        let result = run(Ok(xml.into()));
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert!(result.remediation.is_empty());
+    }
+
+    #[test]
+    fn warns_when_the_notebooks_cannot_be_read() {
+        let result = run(Err("RPC server unavailable".into()));
+        assert_eq!(result.status, CheckStatus::Warning);
+        assert_eq!(result.evidence[0].key, "GetHierarchy");
+
+        let result = run(Ok("<Notebooks><Notebook".into()));
+        assert_eq!(result.status, CheckStatus::Warning);
+        assert!(result.message.contains("Failed to parse OneNote hierarchy"));
+    }
+
+    #[test]
+    fn warns_when_no_notebooks_are_open() {
+        let result = run(Ok("<Notebooks/>".into()));
+        assert_eq!(result.status, CheckStatus::Warning);
+        assert_eq!(result.message, Locale::En.t("locked_sections.no_notebooks", &[]));
+    }
+}
@@ -0,0 +1,268 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Locale;
+use crate::types::{ScanError, ScanResult};
+
+use super::host::{FakeHost, HostProbe, PathKind, VolumeSpace};
+use super::policy::Policy;
+use super::registry::{FakeRegistry, Hive, RegValue, RegistryReader};
+use super::{CheckRegistry, NoopObserver, ScanContext};
+
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Everything a scan read from the machine, plus the result it produced.
+/// Replaying a snapshot reruns the checks against the recorded data only.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub version: u32,
+    pub tool_version: String,
+    pub captured_at: String,
+    pub registry: FakeRegistry,
+    pub host: FakeHost,
+    /// Whether the captured machine could be inspected at all. Snapshots
+    /// written before this was recorded all came from Windows.
+    #[serde(default = "supported_by_default")]
+    pub supported: bool,
+    /// Policy the scan ran under, so a replay applies the same thresholds.
+    #[serde(default)]
+    pub policy: Policy,
+    pub result: ScanResult,
+}
+
+impl Snapshot {
+    pub fn load(path: &Path) -> Result<Self, ScanError> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| ScanError::Unexpected(format!("Failed to read snapshot: {}", e)))?;
+        let snapshot: Snapshot = serde_json::from_str(&json)
+            .map_err(|e| ScanError::Unexpected(format!("Invalid snapshot: {}", e)))?;
+        if snapshot.version > SNAPSHOT_VERSION {
+            return Err(ScanError::Unexpected(format!(
+                "Snapshot version {} is newer than supported version {}",
+                snapshot.version, SNAPSHOT_VERSION
+            )));
+        }
+        Ok(snapshot)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ScanError> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| ScanError::Unexpected(format!("Failed to serialize snapshot: {}", e)))?;
+        std::fs::write(path, json)
+            .map_err(|e| ScanError::Unexpected(format!("Failed to write snapshot: {}", e)))
+    }
+
+    /// Context that serves every probe from the recorded data.
+    pub fn context(&self) -> ScanContext {
+        ScanContext {
+            supported: self.supported,
+            ..ScanContext::new(Arc::new(self.registry.clone()), Arc::new(self.host.clone()))
+        }
+        .with_policy(Arc::new(self.policy.clone()))
+    }
+}
+
+fn supported_by_default() -> bool {
+    true
+}
+
+/// Wraps live backends and copies every successful probe into in-memory
+/// fakes, which become the snapshot.
+struct Recorder {
+    registry: Arc<dyn RegistryReader>,
+    host: Arc<dyn HostProbe>,
+    captured: Mutex<(FakeRegistry, FakeHost)>,
+}
+
+impl RegistryReader for Recorder {
+    fn open_key(&self, hive: Hive, path: &str) -> bool {
+        let exists = self.registry.open_key(hive, path);
+        if exists {
+            self.captured.lock().unwrap().0.insert_key(hive, path);
+        }
+        exists
+    }
+
+    fn read_value(&self, hive: Hive, path: &str, name: &str) -> Option<RegValue> {
+        let value = self.registry.read_value(hive, path, name);
+        if let Some(ref v) = value {
+            self.captured
+                .lock()
+                .unwrap()
+                .0
+                .insert_value(hive, path, name, v.clone());
+        }
+        value
+    }
+
+    fn subkeys(&self, hive: Hive, path: &str) -> Vec<String> {
+        let names = self.registry.subkeys(hive, path);
+        let mut captured = self.captured.lock().unwrap();
+        for name in &names {
+            captured.0.insert_key(hive, &format!("{}\\{}", path, name));
+        }
+        names
+    }
+}
+
+impl HostProbe for Recorder {
+    fn env_var(&self, name: &str) -> Option<String> {
+        let value = self.host.env_var(name);
+        if let Some(ref v) = value {
+            self.captured.lock().unwrap().1.insert_env(name, v);
+        }
+        value
+    }
+
+    fn path_kind(&self, path: &Path) -> Option<PathKind> {
+        let kind = self.host.path_kind(path);
+        if let Some(k) = kind {
+            self.captured.lock().unwrap().1.insert_path(path, k);
+        }
+        kind
+    }
+
+    fn dir_size(&self, path: &Path) -> Option<u64> {
+        let size = self.host.dir_size(path);
+        if let Some(s) = size {
+            self.captured.lock().unwrap().1.insert_dir_size(path, s);
+        }
+        size
+    }
+
+    fn volume_space(&self, path: &Path) -> Option<VolumeSpace> {
+        let volume = self.host.volume_space(path);
+        if let Some(ref v) = volume {
+            self.captured.lock().unwrap().1.insert_volume(path, v.clone());
+        }
+        volume
+    }
+
+    fn com_activation(&self, prog_id: &str) -> Result<(), String> {
+        let outcome = self.host.com_activation(prog_id);
+        self.captured
+            .lock()
+            .unwrap()
+            .1
+            .insert_com(prog_id, outcome.clone());
+        outcome
+    }
+
+    fn onenote_hierarchy(&self) -> Result<String, String> {
+        let outcome = self.host.onenote_hierarchy();
+        self.captured.lock().unwrap().1.set_hierarchy(outcome.clone());
+        outcome
+    }
+}
+
+/// Run a scan against `ctx` while recording everything it touches.
+pub fn capture(checks: &CheckRegistry, ctx: ScanContext) -> Result<Snapshot, ScanError> {
+    let recorder = Arc::new(Recorder {
+        registry: ctx.registry,
+        host: ctx.host,
+        captured: Mutex::new((FakeRegistry::new(), FakeHost::new())),
+    });
+    let recording_ctx = ScanContext {
+        supported: ctx.supported,
+        ..ScanContext::new(recorder.clone(), recorder.clone())
+    };
+    let recording_ctx = Arc::new(
+        recording_ctx
+            .with_timeout(ctx.timeout)
+            .with_cancel_token(ctx.cancel)
+            .with_policy(Arc::clone(&ctx.policy))
+            .with_locale(ctx.locale),
+    );
+
+    let result = super::run_checks(checks, &recording_ctx, &NoopObserver)?;
+
+    let (registry, host) = recorder.captured.lock().unwrap().clone();
+    Ok(Snapshot {
+        version: SNAPSHOT_VERSION,
+        tool_version: env!("CARGO_PKG_VERSION").into(),
+        captured_at: result.timestamp.clone(),
+        registry,
+        host,
+        supported: ctx.supported,
+        policy: (*ctx.policy).clone(),
+        result,
+    })
+}
+
+/// Rerun the checks against a previously captured snapshot, producing
+/// messages in `locale`. `checks` should include the rules of the snapshot's
+/// policy.
+pub fn replay(
+    checks: &CheckRegistry,
+    snapshot: &Snapshot,
+    locale: Locale,
+) -> Result<ScanResult, ScanError> {
+    let ctx = Arc::new(snapshot.context().with_locale(locale));
+    super::run_checks(checks, &ctx, &NoopObserver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::fixtures::{context, office_root, HIERARCHY};
+    use crate::checks::registry::FakeRegistry;
+
+    /// The parts of a result a replay must reproduce; timings differ.
+    fn verdicts(result: &ScanResult) -> serde_json::Value {
+        let mut value = serde_json::to_value(result).unwrap();
+        let scan = value.as_object_mut().unwrap();
+        scan.remove("timestamp");
+        scan.remove("durationMs");
+        for check in value["checks"].as_array_mut().unwrap() {
+            check.as_object_mut().unwrap().remove("durationMs");
+        }
+        value
+    }
+
+    fn round_trip(ctx: ScanContext) -> (Snapshot, ScanResult) {
+        let checks = CheckRegistry::builtin();
+        let snapshot = capture(&checks, ctx).unwrap();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let loaded: Snapshot = serde_json::from_str(&json).unwrap();
+        let replayed = replay(&checks, &loaded, Locale::En).unwrap();
+        (loaded, replayed)
+    }
+
+    #[test]
+    fn replay_reproduces_the_captured_scan() {
+        let registry = office_root(office_root(FakeRegistry::new(), "16.0", "OneNote"), "16.0", "Word");
+        let host = FakeHost::new()
+            .with_com("OneNote.Application", Ok(()))
+            .with_com("Word.Application", Err("Class not registered".into()))
+            .with_hierarchy(Ok(HIERARCHY.into()));
+        let (snapshot, replayed) = round_trip(context(registry, host));
+
+        assert!(snapshot.supported);
+        assert_eq!(verdicts(&replayed), verdicts(&snapshot.result));
+    }
+
+    #[test]
+    fn replay_keeps_an_unsupported_system_unsupported() {
+        let ctx = ScanContext {
+            supported: false,
+            ..context(FakeRegistry::new(), FakeHost::new())
+        };
+        let (snapshot, replayed) = round_trip(ctx);
+
+        assert!(!snapshot.supported);
+        assert_eq!(replayed.os_info, "Non-Windows OS (checks unavailable)");
+        assert_eq!(verdicts(&replayed), verdicts(&snapshot.result));
+    }
+
+    #[test]
+    fn older_snapshots_replay_as_supported() {
+        let (snapshot, _) = round_trip(context(FakeRegistry::new(), FakeHost::new()));
+        let mut json = serde_json::to_value(&snapshot).unwrap();
+        json.as_object_mut().unwrap().remove("supported");
+        let loaded: Snapshot = serde_json::from_value(json).unwrap();
+        assert!(loaded.supported);
+    }
+}
@@ -1,130 +1,309 @@
-use crate::types::{CheckResult, CheckStatus};
+use crate::types::{CheckCategory, CheckResult, CheckStatus, Severity};
 
-#[cfg(windows)]
-use windows::Win32::System::Registry::HKEY_CURRENT_USER;
+use super::fix::RegistryFix;
+use super::policy::DwordPolicy;
+use super::registry::{Hive, RegValue};
+use super::{Check, ScanContext};
 
-#[cfg(windows)]
-fn detect_onenote_version() -> Option<&'static str> {
-    if super::registry::find_office_install_root("OneNote", "16.0").is_some() {
-        Some("16.0")
-    } else if super::registry::find_office_install_root("OneNote", "15.0").is_some() {
-        Some("15.0")
-    } else {
-        None
+pub struct AutoSyncCheck;
+
+impl Check for AutoSyncCheck {
+    fn id(&self) -> &'static str {
+        "sync_auto"
+    }
+
+    fn label(&self) -> &'static str {
+        "OneNote Auto-Sync"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Sync
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Minor
+    }
+
+    fn depends_on(&self) -> &'static [&'static str] {
+        &["onenote"]
+    }
+
+    fn run(&self, ctx: &ScanContext) -> CheckResult {
+        if !ctx.is_supported() {
+            return skipped(ctx, self.id(), self.label());
+        }
+        check_auto_sync(ctx)
+    }
+
+    fn fix(&self, ctx: &ScanContext) -> Option<RegistryFix> {
+        let install = ctx.onenote_install()?;
+        dword_fix(
+            ctx,
+            auto_sync_key(&install.version),
+            "SyncStateOffline",
+            &ctx.policy().sync.auto_sync,
+            "sync_auto.fix",
+        )
     }
 }
 
-#[cfg(windows)]
-fn version_suffix(version: &str) -> String {
+pub struct FullDownloadCheck;
+
+impl Check for FullDownloadCheck {
+    fn id(&self) -> &'static str {
+        "sync_download"
+    }
+
+    fn label(&self) -> &'static str {
+        "OneNote Full Download"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Sync
+    }
+
+    fn depends_on(&self) -> &'static [&'static str] {
+        &["onenote"]
+    }
+
+    fn run(&self, ctx: &ScanContext) -> CheckResult {
+        if !ctx.is_supported() {
+            return skipped(ctx, self.id(), self.label());
+        }
+        check_full_download(ctx)
+    }
+
+    fn fix(&self, ctx: &ScanContext) -> Option<RegistryFix> {
+        let install = ctx.onenote_install()?;
+        dword_fix(
+            ctx,
+            full_download_key(&install.version),
+            "DeferFdoDownload",
+            &ctx.policy().sync.full_download,
+            "sync_download.fix",
+        )
+    }
+}
+
+/// Catalog keys of OneNote's sync options page.
+const ONENOTE_SYNC_MENU: &[&str] = &["menu.onenote", "menu.file", "menu.options", "menu.sync"];
+
+/// Appended to the Office version for versions whose settings keys have not
+/// been verified.
+fn unverified_note(ctx: &ScanContext, version: &str) -> String {
     if version == "16.0" {
-        format!("(Office {})", version)
+        String::new()
     } else {
-        format!("(Office {} \u{2014} unverified; check manually if unsure)", version)
+        ctx.t("sync.unverified", &[])
     }
 }
 
-#[cfg(windows)]
-pub fn check_auto_sync() -> CheckResult {
-    let version = match detect_onenote_version() {
-        Some(v) => v,
-        None => {
-            return CheckResult {
-                id: "sync_auto".into(),
-                label: "OneNote Auto-Sync".into(),
-                status: CheckStatus::Skipped,
-                message: "Skipped \u{2014} OneNote Desktop not installed".into(),
-                remediation: None,
-            };
-        }
+fn auto_sync_key(version: &str) -> String {
+    format!("Software\\Microsoft\\Office\\{}\\OneNote\\Options\\Save", version)
+}
+
+fn full_download_key(version: &str) -> String {
+    format!("Software\\Microsoft\\Office\\{}\\OneNote\\Options", version)
+}
+
+/// Set `name` under HKCU `path` to the first value the policy accepts.
+/// `description` is the catalog key of what the change does.
+fn dword_fix(
+    ctx: &ScanContext,
+    path: String,
+    name: &str,
+    policy: &DwordPolicy,
+    description: &str,
+) -> Option<RegistryFix> {
+    let value = *policy.pass_values.first()?;
+    Some(RegistryFix {
+        hive: Hive::CurrentUser,
+        path,
+        name: name.into(),
+        value: RegValue::Dword(value),
+        description: ctx.t(
+            "sync.fix_dword",
+            &[("action", &ctx.t(description, &[])), ("name", &name), ("value", &value)],
+        ),
+    })
+}
+
+fn dword_display(value: Option<u32>) -> String {
+    value.map_or_else(|| "(not set)".into(), |v| v.to_string())
+}
+
+pub fn check_auto_sync(ctx: &ScanContext) -> CheckResult {
+    // The registry install root tells us which Office hive holds the settings.
+    // OneNote may still pass via COM alone, e.g. for Store deployments.
+    let version = match ctx.onenote_install() {
+        Some(install) => install.version.as_str(),
+        None => return skipped(ctx, "sync_auto", "OneNote Auto-Sync"),
     };
 
-    let suffix = version_suffix(version);
-    let subkey = format!(
-        "Software\\Microsoft\\Office\\{}\\OneNote\\Options\\Save",
-        version
-    );
-    let value = super::registry::read_reg_dword(HKEY_CURRENT_USER, &subkey, "SyncStateOffline");
+    let note = unverified_note(ctx, version);
+    let subkey = auto_sync_key(version);
+    let value = ctx.registry().read_dword(Hive::CurrentUser, &subkey, "SyncStateOffline");
+    let evidence = vec![Hive::CurrentUser.evidence(&subkey, "SyncStateOffline", dword_display(value))];
 
-    match value {
-        Some(0) | None => CheckResult {
+    if ctx.policy().sync.auto_sync.passes(value) {
+        CheckResult {
             id: "sync_auto".into(),
             label: "OneNote Auto-Sync".into(),
             status: CheckStatus::Pass,
-            message: format!("Automatic sync is enabled {}", suffix),
-            remediation: None,
-        },
-        Some(_) => CheckResult {
+            message: ctx.t("sync_auto.enabled", &[("office_version", &version), ("unverified", &note)]),
+            remediation: Vec::new(),
+            evidence,
+            ..Default::default()
+        }
+    } else {
+        CheckResult {
             id: "sync_auto".into(),
             label: "OneNote Auto-Sync".into(),
             status: CheckStatus::Warning,
-            message: format!("Automatic sync is disabled {}", suffix),
-            remediation: Some(
-                "Open OneNote \u{2192} File \u{2192} Options \u{2192} Sync and enable 'Sync notebooks automatically'. This ensures your notebooks are up-to-date before migration.".into()
-            ),
-        },
+            message: ctx.t("sync_auto.disabled", &[("office_version", &version), ("unverified", &note)]),
+            remediation: vec![
+                ctx.locale().menu(ONENOTE_SYNC_MENU),
+                ctx.locale().instruction("sync_auto.disabled.remediation", &[]),
+            ],
+            evidence,
+            ..Default::default()
+        }
     }
 }
 
-#[cfg(windows)]
-pub fn check_full_download() -> CheckResult {
-    let version = match detect_onenote_version() {
-        Some(v) => v,
-        None => {
-            return CheckResult {
-                id: "sync_download".into(),
-                label: "OneNote Full Download".into(),
-                status: CheckStatus::Skipped,
-                message: "Skipped \u{2014} OneNote Desktop not installed".into(),
-                remediation: None,
-            };
-        }
+pub fn check_full_download(ctx: &ScanContext) -> CheckResult {
+    let version = match ctx.onenote_install() {
+        Some(install) => install.version.as_str(),
+        None => return skipped(ctx, "sync_download", "OneNote Full Download"),
     };
 
-    let suffix = version_suffix(version);
-    let subkey = format!(
-        "Software\\Microsoft\\Office\\{}\\OneNote\\Options",
-        version
-    );
-    let value = super::registry::read_reg_dword(HKEY_CURRENT_USER, &subkey, "DeferFdoDownload");
+    let note = unverified_note(ctx, version);
+    let subkey = full_download_key(version);
+    let value = ctx.registry().read_dword(Hive::CurrentUser, &subkey, "DeferFdoDownload");
+    let evidence = vec![Hive::CurrentUser.evidence(&subkey, "DeferFdoDownload", dword_display(value))];
 
-    match value {
-        Some(0) => CheckResult {
+    if ctx.policy().sync.full_download.passes(value) {
+        CheckResult {
             id: "sync_download".into(),
             label: "OneNote Full Download".into(),
             status: CheckStatus::Pass,
-            message: format!("Full file and image download is enabled {}", suffix),
-            remediation: None,
-        },
-        _ => CheckResult {
+            message: ctx.t("sync_download.enabled", &[("office_version", &version), ("unverified", &note)]),
+            remediation: Vec::new(),
+            evidence,
+            ..Default::default()
+        }
+    } else {
+        CheckResult {
             id: "sync_download".into(),
             label: "OneNote Full Download".into(),
             status: CheckStatus::Warning,
-            message: format!("Full file and image download is not enabled {}", suffix),
-            remediation: Some(
-                "Open OneNote \u{2192} File \u{2192} Options \u{2192} Sync and enable 'Download all files and images'. This ensures all attachments and embedded images are cached locally before migration, preventing missing content.".into()
-            ),
-        },
+            message: ctx.t("sync_download.disabled", &[("office_version", &version), ("unverified", &note)]),
+            remediation: vec![
+                ctx.locale().menu(ONENOTE_SYNC_MENU),
+                ctx.locale().instruction("sync_download.disabled.remediation", &[]),
+            ],
+            evidence,
+            ..Default::default()
+        }
     }
 }
 
-#[cfg(not(windows))]
-pub fn check_auto_sync() -> CheckResult {
+/// Whether OneNote `version` downloads all files and images, as the policy
+/// defines it.
+pub fn full_download_enabled(ctx: &ScanContext, version: &str) -> bool {
+    let value = ctx
+        .registry()
+        .read_dword(Hive::CurrentUser, &full_download_key(version), "DeferFdoDownload");
+    ctx.policy().sync.full_download.passes(value)
+}
+
+fn skipped(ctx: &ScanContext, id: &str, label: &str) -> CheckResult {
     CheckResult {
-        id: "sync_auto".into(),
-        label: "OneNote Auto-Sync".into(),
+        id: id.into(),
+        label: label.into(),
         status: CheckStatus::Skipped,
-        message: "Skipped \u{2014} OneNote Desktop not installed".into(),
-        remediation: None,
+        message: ctx.t("sync.skipped", &[]),
+        remediation: Vec::new(),
+        ..Default::default()
     }
 }
 
-#[cfg(not(windows))]
-pub fn check_full_download() -> CheckResult {
-    CheckResult {
-        id: "sync_download".into(),
-        label: "OneNote Full Download".into(),
-        status: CheckStatus::Skipped,
-        message: "Skipped \u{2014} OneNote Desktop not installed".into(),
-        remediation: None,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::fixtures::{context, office_root};
+    use crate::checks::host::FakeHost;
+    use crate::checks::registry::FakeRegistry;
+
+    fn onenote(version: &str) -> FakeRegistry {
+        office_root(FakeRegistry::new(), version, "OneNote")
+    }
+
+    fn with_dword(registry: FakeRegistry, path: String, name: &str, value: u32) -> FakeRegistry {
+        registry.with_value(Hive::CurrentUser, &path, name, RegValue::Dword(value))
+    }
+
+    #[test]
+    fn auto_sync_passes_when_enabled() {
+        let registry = with_dword(onenote("16.0"), auto_sync_key("16.0"), "SyncStateOffline", 0);
+        let result = check_auto_sync(&context(registry, FakeHost::new()));
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert_eq!(result.evidence[0].value, "0");
+    }
+
+    #[test]
+    fn auto_sync_warns_when_disabled() {
+        let registry = with_dword(onenote("16.0"), auto_sync_key("16.0"), "SyncStateOffline", 1);
+        let result = check_auto_sync(&context(registry, FakeHost::new()));
+        assert_eq!(result.status, CheckStatus::Warning);
+        assert!(!result.remediation.is_empty());
+    }
+
+    #[test]
+    fn auto_sync_passes_if_missing() {
+        let result = check_auto_sync(&context(onenote("16.0"), FakeHost::new()));
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert_eq!(result.evidence[0].value, "(not set)");
+    }
+
+    #[test]
+    fn full_download_warns_if_missing() {
+        let result = check_full_download(&context(onenote("16.0"), FakeHost::new()));
+        assert_eq!(result.status, CheckStatus::Warning);
+    }
+
+    #[test]
+    fn full_download_passes_when_enabled() {
+        let registry = with_dword(onenote("16.0"), full_download_key("16.0"), "DeferFdoDownload", 0);
+        let ctx = context(registry, FakeHost::new());
+        assert_eq!(check_full_download(&ctx).status, CheckStatus::Pass);
+        assert!(full_download_enabled(&ctx, "16.0"));
+    }
+
+    #[test]
+    fn older_office_reads_its_own_keys_and_is_marked_unverified() {
+        let registry = with_dword(onenote("15.0"), auto_sync_key("15.0"), "SyncStateOffline", 1);
+        let ctx = context(registry, FakeHost::new());
+        let result = check_auto_sync(&ctx);
+        assert_eq!(result.status, CheckStatus::Warning);
+        assert!(result.message.contains(&ctx.t("sync.unverified", &[])));
+        assert!(result.evidence[0].source.contains("Office\\15.0\\OneNote"));
+    }
+
+    #[test]
+    fn skipped_without_install_root() {
+        let ctx = context(FakeRegistry::new(), FakeHost::new());
+        assert_eq!(check_auto_sync(&ctx).status, CheckStatus::Skipped);
+        assert_eq!(check_full_download(&ctx).status, CheckStatus::Skipped);
+    }
+
+    #[test]
+    fn fix_sets_first_passing_value() {
+        let ctx = context(onenote("16.0"), FakeHost::new());
+        let fix = FullDownloadCheck.fix(&ctx).unwrap();
+        assert_eq!(fix.hive, Hive::CurrentUser);
+        assert_eq!(fix.path, full_download_key("16.0"));
+        assert_eq!(fix.value, RegValue::Dword(0));
+        assert!(AutoSyncCheck.fix(&context(FakeRegistry::new(), FakeHost::new())).is_none());
     }
 }
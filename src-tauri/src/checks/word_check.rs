@@ -1,80 +1,159 @@
-use crate::types::{CheckResult, CheckStatus};
+use crate::types::{CheckCategory, CheckResult, CheckStatus, Evidence, RemediationStep};
 
-#[cfg(windows)]
-pub fn check() -> CheckResult {
-    let registry_info = detect_via_registry();
-    let com_result = super::registry::test_com_activation("Word.Application");
+use super::host;
+use super::registry;
+use super::{Check, ScanContext};
+
+pub struct WordCheck;
+
+impl Check for WordCheck {
+    fn id(&self) -> &'static str {
+        "word"
+    }
+
+    fn label(&self) -> &'static str {
+        "Word"
+    }
+
+    fn category(&self) -> CheckCategory {
+        CheckCategory::Office
+    }
+
+    fn run(&self, ctx: &ScanContext) -> CheckResult {
+        if !ctx.is_supported() {
+            return unsupported(ctx);
+        }
+        check(ctx)
+    }
+}
+
+pub fn check(ctx: &ScanContext) -> CheckResult {
+    let mut evidence = Vec::new();
+    let registry_info = detect_via_registry(ctx, &mut evidence);
+    let com_result = ctx.host().com_activation("Word.Application");
+    evidence.push(host::com_evidence("Word.Application", &com_result));
 
     match (&registry_info, &com_result) {
         (Some(info), Ok(())) => CheckResult {
             id: "word".into(),
             label: "Word".into(),
             status: CheckStatus::Pass,
-            message: format!("{} — COM automation verified.", info),
-            remediation: None,
+            message: ctx.t("office.com_verified", &[("install", info)]),
+            remediation: Vec::new(),
+            evidence,
+            ..Default::default()
         },
         (None, Ok(())) => CheckResult {
             id: "word".into(),
             label: "Word".into(),
             status: CheckStatus::Warning,
-            message: "Word COM automation works but installation not found via standard registry paths.".into(),
-            remediation: Some(
-                "The export should work, but your Office installation appears non-standard \
-                 (e.g. Microsoft Store or MSIX deployment). For best reliability, consider \
-                 reinstalling Office as a standard Click-to-Run installation from office.com."
-                    .into(),
-            ),
+            message: ctx.t("office.non_standard", &[("app", &"Word")]),
+            remediation: vec![
+                ctx.locale().instruction("office.non_standard.remediation", &[]),
+                RemediationStep::link(ctx.t("office.install", &[]), registry::OFFICE_URL),
+            ],
+            evidence,
+            ..Default::default()
         },
         (Some(info), Err(e)) => CheckResult {
             id: "word".into(),
             label: "Word".into(),
             status: CheckStatus::Fail,
-            message: format!("{} — but COM automation failed: {}", info, e),
-            remediation: Some(
-                "Word is installed but COM automation is not working. Try repairing \
-                 your Office installation: Settings \u{2192} Apps \u{2192} Microsoft Office \
-                 \u{2192} Modify \u{2192} Online Repair."
-                    .into(),
-            ),
+            message: ctx.t("office.com_failed", &[("install", info), ("error", e)]),
+            remediation: vec![
+                ctx.locale().instruction("office.com_failed.remediation", &[("app", &"Word")]),
+                ctx.locale().menu(registry::OFFICE_REPAIR_MENU),
+            ],
+            evidence,
+            ..Default::default()
         },
         (None, Err(_)) => CheckResult {
             id: "word".into(),
             label: "Word".into(),
             status: CheckStatus::Fail,
-            message: "Word desktop application not found.".into(),
-            remediation: Some(
-                "Install Microsoft Office (Desktop) with Word included. \
-                 Office 2013 or later is required. Word is needed for \
-                 data rendering during the export process."
-                    .into(),
-            ),
+            message: ctx.t("word.not_found", &[]),
+            remediation: vec![ctx.locale().instruction("word.not_found.remediation", &[])],
+            evidence,
+            ..Default::default()
         },
     }
 }
 
-#[cfg(windows)]
-fn detect_via_registry() -> Option<String> {
-    for version in &["16.0", "15.0"] {
-        if let Some(path) = super::registry::find_office_install_root("Word", version) {
-            return Some(format!("Version {} at {}", version, path));
-        }
+fn detect_via_registry(ctx: &ScanContext, evidence: &mut Vec<Evidence>) -> Option<String> {
+    if let Some(install) = ctx.word_install() {
+        evidence.push(install.evidence());
+        return Some(ctx.t(
+            "office.install_root",
+            &[("version", &install.version), ("path", &install.path)],
+        ));
     }
-    if let Some(c2r) = super::registry::find_click_to_run() {
-        if !super::registry::is_c2r_app_excluded(&c2r.product_ids, "Word") {
-            let ver = c2r.version.as_deref().unwrap_or("unknown");
-            return Some(format!("Click-to-Run {} (v{})", c2r.product_ids, ver));
+    if let Some(c2r) = ctx.click_to_run() {
+        if !registry::is_c2r_app_excluded(ctx.registry(), &c2r.product_ids, "Word") {
+            evidence.extend(c2r.evidence());
+            let ver = c2r.version.clone().unwrap_or_else(|| ctx.t("common.unknown", &[]));
+            return Some(ctx.t(
+                "office.click_to_run",
+                &[("products", &c2r.product_ids), ("version", &ver)],
+            ));
         }
     }
     None
 }
 
-#[cfg(not(windows))]
-pub fn check() -> CheckResult {
+fn unsupported(ctx: &ScanContext) -> CheckResult {
     CheckResult {
         id: "word".into(),
         label: "Word".into(),
         status: CheckStatus::Fail,
-        message: "Not running on Windows — cannot check Word.".into(),
-        remediation: Some("This tool must be run on Windows.".into()),
+        message: ctx.t("word.unsupported", &[]),
+        remediation: vec![ctx.locale().instruction("common.windows_required", &[])],
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::fixtures::{context, office_root};
+    use crate::checks::host::FakeHost;
+    use crate::checks::registry::FakeRegistry;
+    use crate::i18n::Locale;
+
+    fn com(outcome: Result<(), String>) -> FakeHost {
+        FakeHost::new().with_com("Word.Application", outcome)
+    }
+
+    #[test]
+    fn install_root_with_com_passes() {
+        let registry = office_root(FakeRegistry::new(), "16.0", "Word");
+        assert_eq!(check(&context(registry, com(Ok(())))).status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn older_install_root_is_found() {
+        let registry = office_root(FakeRegistry::new(), "15.0", "Word");
+        let result = check(&context(registry, com(Ok(()))));
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert!(result.evidence[0].source.contains("Office\\15.0\\Word"));
+    }
+
+    #[test]
+    fn install_root_without_com_fails() {
+        let registry = office_root(FakeRegistry::new(), "16.0", "Word");
+        assert_eq!(check(&context(registry, com(Err("denied".into())))).status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn onenote_install_does_not_count_for_word() {
+        let registry = office_root(FakeRegistry::new(), "16.0", "OneNote");
+        let result = check(&context(registry, com(Err("no class".into()))));
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert_eq!(result.message, Locale::En.t("word.not_found", &[]));
+    }
+
+    #[test]
+    fn missing_com_result_fails() {
+        let result = check(&context(FakeRegistry::new(), FakeHost::new()));
+        assert_eq!(result.status, CheckStatus::Fail);
     }
 }
@@ -0,0 +1,382 @@
+//! Headless mode for unattended runs, e.g. from Intune or SCCM scripts:
+//! the same checks as the app, without creating a window.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::checks::policy::Policy;
+use crate::checks::scheduler::CancelToken;
+use crate::checks::{CheckRegistry, ScanObserver, DEFAULT_CHECK_TIMEOUT};
+use crate::i18n::Locale;
+use crate::report::fleet::{Fleet, FleetFormat, FleetSummary};
+use crate::report::redact::Redactor;
+use crate::report::{self, ReportFormat};
+use crate::types::{CheckResult, CheckStatus};
+
+/// Exit code when no verdict could be reached: bad arguments, an invalid
+/// policy file, an output path that cannot be written, or no check that
+/// could run.
+pub const EXIT_ERROR: i32 = 3;
+
+const USAGE: &str = "\
+Usage:
+  onenote-to-joplin scan [options]
+  onenote-to-joplin list-checks [--locale <en|nl|de>]
+  onenote-to-joplin aggregate <folder> [options]
+  onenote-to-joplin schema
+  onenote-to-joplin help
+
+Scan options:
+  --format <json|markdown|html|junit|sarif>
+                            Output format (default: json, described by `schema`)
+  --template <path>         Render the report with this template instead; values
+                            are escaped for its extension (.md, .html or .xml)
+  --out <path>              Write the report to a file instead of stdout
+  --redact                  Replace the user profile folder, user and machine
+                            names with placeholders, for sharing the report
+  --checks <id,id,...>      Run only these checks and the checks they depend on
+  --export-dir <path>       Folder the notebooks will be exported to, for the
+                            disk space check (default: from the policy file)
+  --timeout <seconds>       Time limit per check, at least 1 (default: 60)
+  --locale <en|nl|de>       Language of messages (default: Windows display language)
+
+Aggregate options:
+  <folder>                  JSON reports to summarize, one per machine, each
+                            named after its machine (e.g. PC-0042.json)
+  --format <markdown|html|csv>
+                            Output format (default: markdown)
+  --out <path>              Write the summary to a file instead of stdout
+  --locale <en|nl|de>       Language of headings (default: Windows display language)
+
+Exit codes: 0 ready, 1 warnings, 2 failures, 3 the scan could not run.
+This is a Windows GUI program, so cmd and PowerShell return before it ends.
+To wait for the exit code:
+  cmd:         start /wait onenote-to-joplin.exe scan --out report.json
+               echo %ERRORLEVEL%
+  PowerShell:  $p = Start-Process onenote-to-joplin.exe -ArgumentList 'scan','--out','report.json' -Wait -PassThru
+               $p.ExitCode
+Without arguments the app window opens.";
+
+#[derive(Debug)]
+pub struct ScanArgs {
+    pub format: ReportFormat,
+    /// A user template, used instead of `format`.
+    pub template: Option<PathBuf>,
+    pub out: Option<PathBuf>,
+    pub redact: bool,
+    /// Empty runs every check.
+    pub checks: Vec<String>,
+    /// Overrides the policy's export folder.
+    pub export_dir: Option<String>,
+    pub timeout: Duration,
+    pub locale: Option<Locale>,
+}
+
+#[derive(Debug)]
+pub struct AggregateArgs {
+    pub folder: PathBuf,
+    pub format: FleetFormat,
+    pub out: Option<PathBuf>,
+    pub locale: Option<Locale>,
+}
+
+#[derive(Debug)]
+pub enum Command {
+    Scan(ScanArgs),
+    /// Summarize the JSON reports of many machines.
+    Aggregate(AggregateArgs),
+    ListChecks { locale: Option<Locale> },
+    /// Print the JSON Schema of the JSON report.
+    Schema,
+    Help,
+}
+
+/// Parse the arguments after the program name.
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    let Some((command, options)) = args.split_first() else {
+        return Ok(Command::Help);
+    };
+    let mut scan = ScanArgs {
+        format: ReportFormat::Json,
+        template: None,
+        out: None,
+        redact: false,
+        checks: Vec::new(),
+        export_dir: None,
+        timeout: DEFAULT_CHECK_TIMEOUT,
+        locale: None,
+    };
+    let mut folder = None;
+    let mut fleet_format = FleetFormat::default();
+
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let mut value = || {
+            options
+                .next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value", option))
+        };
+        match option.as_str() {
+            "--locale" if matches!(command.as_str(), "scan" | "list-checks" | "aggregate") => {
+                let code = value()?;
+                scan.locale = Some(
+                    Locale::from_code(&code).ok_or_else(|| format!("Unknown locale '{}'", code))?,
+                );
+            }
+            "--format" if command == "scan" => {
+                let name = value()?;
+                scan.format = ReportFormat::from_name(&name).ok_or_else(|| format!("Unknown format '{}'", name))?;
+            }
+            "--format" if command == "aggregate" => {
+                let name = value()?;
+                fleet_format = FleetFormat::from_name(&name).ok_or_else(|| format!("Unknown format '{}'", name))?;
+            }
+            "--template" if command == "scan" => scan.template = Some(PathBuf::from(value()?)),
+            "--out" if command == "scan" || command == "aggregate" => scan.out = Some(PathBuf::from(value()?)),
+            "--redact" if command == "scan" => scan.redact = true,
+            "--checks" if command == "scan" => {
+                scan.checks = value()?
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(String::from)
+                    .collect();
+            }
+            "--export-dir" if command == "scan" => scan.export_dir = Some(value()?),
+            "--timeout" if command == "scan" => {
+                let secs = value()?;
+                let secs: u64 = secs
+                    .parse()
+                    .ok()
+                    .filter(|&secs| secs > 0)
+                    .ok_or_else(|| format!("Invalid timeout '{}'", secs))?;
+                scan.timeout = Duration::from_secs(secs);
+            }
+            path if command == "aggregate" && folder.is_none() && !path.starts_with("--") => {
+                folder = Some(PathBuf::from(path));
+            }
+            _ => return Err(format!("Unknown option '{}'", option)),
+        }
+    }
+
+    match command.as_str() {
+        "scan" => Ok(Command::Scan(scan)),
+        "aggregate" => Ok(Command::Aggregate(AggregateArgs {
+            folder: folder.ok_or("aggregate needs a folder of JSON reports")?,
+            format: fleet_format,
+            out: scan.out,
+            locale: scan.locale,
+        })),
+        "list-checks" => Ok(Command::ListChecks { locale: scan.locale }),
+        "schema" => Ok(Command::Schema),
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        other => Err(format!("Unknown command '{}'", other)),
+    }
+}
+
+/// Map the overall verdict to the process exit code. Every check skipped
+/// means nothing was verified, which is no verdict at all.
+pub fn exit_code(overall: &CheckStatus) -> i32 {
+    match overall {
+        CheckStatus::Pass => 0,
+        CheckStatus::Warning => 1,
+        CheckStatus::Fail | CheckStatus::Timeout => 2,
+        CheckStatus::Skipped => EXIT_ERROR,
+    }
+}
+
+/// Run the command in `args` and return the exit code.
+pub fn main(args: &[String]) -> i32 {
+    attach_console();
+    let command = match parse(args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return EXIT_ERROR;
+        }
+    };
+    let outcome = match command {
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(0)
+        }
+        Command::ListChecks { locale } => list_checks(locale.unwrap_or_else(Locale::system)),
+        Command::Schema => print_schema(),
+        Command::Scan(args) => scan(args),
+        Command::Aggregate(args) => aggregate(args),
+    };
+    outcome.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        EXIT_ERROR
+    })
+}
+
+fn list_checks(locale: Locale) -> Result<i32, String> {
+    let policy = Policy::load_configured().map_err(|e| e.to_string())?;
+    for info in CheckRegistry::with_policy(&policy).infos(locale) {
+        println!("{:<24} {}", info.id, info.label);
+    }
+    Ok(0)
+}
+
+fn print_schema() -> Result<i32, String> {
+    let schema = serde_json::to_string_pretty(&report::json::schema())
+        .map_err(|e| format!("Failed to serialize schema: {}", e))?;
+    println!("{}", schema);
+    Ok(0)
+}
+
+fn scan(args: ScanArgs) -> Result<i32, String> {
+    let mut policy = Policy::load_configured().map_err(|e| e.to_string())?;
+    if let Some(dir) = args.export_dir {
+        policy.disk.export_folder = Some(dir);
+    }
+    let locale = args.locale.unwrap_or_else(Locale::system);
+    let result = crate::checks::run_all_checks(
+        Arc::new(policy),
+        locale,
+        &args.checks,
+        args.timeout,
+        CancelToken::new(),
+        &ProgressObserver,
+    )
+    .map_err(|e| e.to_string())?;
+
+    let result = if args.redact {
+        Redactor::for_this_machine().apply(&result)
+    } else {
+        result
+    };
+    let rendered = match &args.template {
+        Some(path) => report::template::render_file(path, &result)?,
+        None => report::render(&result, args.format)?,
+    };
+    match &args.out {
+        Some(path) => {
+            std::fs::write(path, rendered)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            eprintln!("Report written to {}", path.display());
+        }
+        None => println!("{}", rendered),
+    }
+    Ok(exit_code(&result.overall))
+}
+
+fn aggregate(args: AggregateArgs) -> Result<i32, String> {
+    let fleet = Fleet::load_dir(&args.folder)?;
+    for skipped in &fleet.skipped {
+        eprintln!("Skipped {}: {}", skipped.file, skipped.reason);
+    }
+    if fleet.machines.is_empty() {
+        return Err(format!("No scan results found in {}", args.folder.display()));
+    }
+    let summary = FleetSummary::new(&fleet);
+    let locale = args.locale.unwrap_or_else(Locale::system);
+    let rendered = report::fleet::render(&summary, args.format, locale)?;
+    match &args.out {
+        Some(path) => {
+            std::fs::write(path, rendered)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            eprintln!("Summary of {} machines written to {}", summary.machines, path.display());
+        }
+        None => println!("{}", rendered),
+    }
+    Ok(0)
+}
+
+/// Reports each finished check on stderr, keeping stdout for the report.
+struct ProgressObserver;
+
+impl ScanObserver for ProgressObserver {
+    fn check_finished(&self, result: &CheckResult) {
+        let status = format!("{:?}", result.status).to_uppercase();
+        eprintln!("[{:<7}] {} ({} ms)", status, result.label, result.duration_ms);
+    }
+}
+
+/// Release builds use the Windows GUI subsystem and start without a console;
+/// borrow the one of the shell that launched us so output is visible.
+#[cfg(windows)]
+fn attach_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, String> {
+        parse(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn parses_scan_options() {
+        let args = [
+            "scan", "--format", "sarif", "--out", "r.sarif", "--redact",
+            "--checks", "onenote, word,", "--timeout", "5", "--locale", "nl",
+        ];
+        let Ok(Command::Scan(scan)) = parse_args(&args) else {
+            panic!("not a scan");
+        };
+        assert_eq!(scan.format, ReportFormat::Sarif);
+        assert_eq!(scan.out, Some(PathBuf::from("r.sarif")));
+        assert!(scan.redact);
+        assert_eq!(scan.checks, ["onenote", "word"]);
+        assert_eq!(scan.timeout, Duration::from_secs(5));
+        assert_eq!(scan.locale, Some(Locale::Nl));
+    }
+
+    #[test]
+    fn parses_aggregate_with_out() {
+        let Ok(Command::Aggregate(args)) =
+            parse_args(&["aggregate", "reports", "--format", "csv", "--out", "fleet.csv"])
+        else {
+            panic!("not an aggregate");
+        };
+        assert_eq!(args.folder, PathBuf::from("reports"));
+        assert_eq!(args.format, FleetFormat::Csv);
+        assert_eq!(args.out, Some(PathBuf::from("fleet.csv")));
+        assert!(parse_args(&["aggregate", "--out", "fleet.md"]).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        let e = parse_args(&["scan", "--verbose"]).unwrap_err();
+        assert_eq!(e, "Unknown option '--verbose'");
+        let e = parse_args(&["scan", "--out"]).unwrap_err();
+        assert_eq!(e, "--out needs a value");
+        assert!(parse_args(&["scan", "--format", "pdf"]).is_err());
+        assert!(parse_args(&["scan", "--locale", "fr"]).is_err());
+        assert!(parse_args(&["scan", "--timeout", "0"]).is_err());
+        assert!(parse_args(&["scan", "--timeout", "-1"]).is_err());
+        assert!(parse_args(&["scan", "reports"]).is_err());
+        assert!(parse_args(&["audit"]).is_err());
+    }
+
+    #[test]
+    fn rejects_options_of_another_command() {
+        assert!(parse_args(&["aggregate", "reports", "--redact"]).is_err());
+        assert!(parse_args(&["aggregate", "reports", "--format", "sarif"]).is_err());
+        assert!(parse_args(&["scan", "--format", "csv"]).is_err());
+        assert!(parse_args(&["list-checks", "--out", "checks.txt"]).is_err());
+        assert!(parse_args(&["schema", "--locale", "en"]).is_err());
+        let list = parse_args(&["list-checks", "--locale", "de"]);
+        assert!(matches!(list, Ok(Command::ListChecks { locale: Some(Locale::De) })));
+        assert!(matches!(parse_args(&[]), Ok(Command::Help)));
+    }
+
+    #[test]
+    fn exit_codes_follow_the_verdict() {
+        assert_eq!(exit_code(&CheckStatus::Pass), 0);
+        assert_eq!(exit_code(&CheckStatus::Warning), 1);
+        assert_eq!(exit_code(&CheckStatus::Fail), 2);
+        assert_eq!(exit_code(&CheckStatus::Timeout), 2);
+        assert_eq!(exit_code(&CheckStatus::Skipped), EXIT_ERROR);
+    }
+}
@@ -1,11 +1,155 @@
-use crate::types::ScanResult;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use tauri::{AppHandle, Emitter, State};
+
+use crate::checks::policy::Policy;
+use crate::i18n::Locale;
+use crate::checks::scheduler::CancelToken;
+use crate::checks::{CheckRegistry, ScanObserver, DEFAULT_CHECK_TIMEOUT};
+use crate::types::{CheckInfo, CheckResult, ScanResult};
+
+/// Policy loaded at startup, the language scans report in and the cancel
+/// token of the scan currently in flight, if any.
+pub struct ScanState {
+    cancel: Mutex<Option<CancelToken>>,
+    policy: Result<Arc<Policy>, String>,
+    locale: Mutex<Locale>,
+}
+
+impl ScanState {
+    /// A policy that failed to load is reported by every scan rather than
+    /// silently replaced with the defaults.
+    pub fn new(policy: Result<Policy, String>, locale: Locale) -> Self {
+        Self {
+            cancel: Mutex::new(None),
+            policy: policy.map(Arc::new),
+            locale: Mutex::new(locale),
+        }
+    }
+
+    pub fn policy(&self) -> Result<Arc<Policy>, String> {
+        self.policy.clone()
+    }
+
+    pub fn locale(&self) -> Locale {
+        *self.locale.lock().unwrap()
+    }
+
+    /// Register a new scan and return its cancel token. Only one scan runs
+    /// at a time, so that `cancel_scan` always reaches the one in flight.
+    pub(crate) fn begin_scan(&self) -> Result<CancelToken, String> {
+        let mut current = self.cancel.lock().unwrap();
+        if current.is_some() {
+            return Err("A scan is already running".into());
+        }
+        let cancel = CancelToken::new();
+        *current = Some(cancel.clone());
+        Ok(cancel)
+    }
+
+    pub(crate) fn end_scan(&self) {
+        *self.cancel.lock().unwrap() = None;
+    }
+}
+
+/// Forwards scan progress to the frontend as Tauri events.
+struct EventObserver {
+    app: AppHandle,
+}
+
+impl ScanObserver for EventObserver {
+    fn check_started(&self, info: &CheckInfo) {
+        let _ = self.app.emit("scan://check-started", info);
+    }
+
+    fn check_finished(&self, result: &CheckResult) {
+        let _ = self.app.emit("scan://check-finished", result);
+    }
+}
+
+#[tauri::command]
+pub fn list_checks(state: State<'_, ScanState>) -> Result<Vec<CheckInfo>, String> {
+    let policy = state.policy()?;
+    let locale = state.locale();
+    Ok(CheckRegistry::with_policy(&policy).infos(locale))
+}
+
+/// The language scans and reports currently use.
 #[tauri::command]
-pub async fn run_readiness_scan() -> Result<ScanResult, String> {
+pub fn get_locale(state: State<'_, ScanState>) -> Locale {
+    state.locale()
+}
+
+/// Switch scans and reports to `locale`, overriding the Windows display
+/// language. Takes effect from the next scan.
+#[tauri::command]
+pub fn set_locale(state: State<'_, ScanState>, locale: Locale) {
+    *state.locale.lock().unwrap() = locale;
+}
+
+#[tauri::command]
+pub async fn run_readiness_scan(
+    app: AppHandle,
+    state: State<'_, ScanState>,
+    timeout_secs: Option<u64>,
+) -> Result<ScanResult, String> {
+    let timeout = check_timeout(timeout_secs)?;
+    let policy = state.policy()?;
+    let locale = state.locale();
+    let cancel = state.begin_scan()?;
+
     // COM requires STA, so run on a dedicated blocking thread
-    tokio::task::spawn_blocking(|| {
-        crate::checks::run_all_checks().map_err(|e| e.to_string())
+    let observer = EventObserver { app: app.clone() };
+    let result = tokio::task::spawn_blocking(move || {
+        crate::checks::run_all_checks(policy, locale, &[], timeout, cancel, &observer)
+            .map_err(|e| e.to_string())
     })
     .await
-    .map_err(|e| format!("Task join error: {}", e))?
+    .map_err(|e| format!("Task join error: {}", e));
+
+    state.end_scan();
+    let result = result??;
+    super::history::record(&app, result.clone()).await;
+    Ok(result)
+}
+
+/// The per-check timeout asked for, rejecting zero as the CLI does, as every
+/// check would time out at once.
+pub(crate) fn check_timeout(timeout_secs: Option<u64>) -> Result<Duration, String> {
+    match timeout_secs {
+        Some(0) => Err("Invalid timeout '0'".into()),
+        Some(secs) => Ok(Duration::from_secs(secs)),
+        None => Ok(DEFAULT_CHECK_TIMEOUT),
+    }
+}
+
+/// Stop the scan in flight. Checks that are already running are abandoned.
+#[tauri::command]
+pub fn cancel_scan(state: State<'_, ScanState>) {
+    if let Some(cancel) = state.cancel.lock().unwrap().as_ref() {
+        cancel.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_scan_at_a_time() {
+        let state = ScanState::new(Ok(Policy::default()), Locale::En);
+        state.begin_scan().unwrap();
+        assert_eq!(state.begin_scan().unwrap_err(), "A scan is already running");
+
+        state.end_scan();
+        assert!(state.begin_scan().is_ok());
+    }
+
+    #[test]
+    fn a_zero_timeout_is_rejected() {
+        assert_eq!(check_timeout(Some(0)).unwrap_err(), "Invalid timeout '0'");
+        assert_eq!(check_timeout(Some(45)), Ok(Duration::from_secs(45)));
+        assert_eq!(check_timeout(None), Ok(DEFAULT_CHECK_TIMEOUT));
+    }
 }
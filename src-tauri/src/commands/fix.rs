@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::checks::fix::{self, FixChange, Journal};
+use crate::checks::policy::Policy;
+use crate::checks::{CheckRegistry, ScanContext};
+use crate::i18n::Locale;
+use crate::types::CheckResult;
+
+use super::checks::ScanState;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixOutcome {
+    pub change: FixChange,
+    /// The check rerun after the fix; `None` for a dry run.
+    pub result: Option<CheckResult>,
+}
+
+/// Apply the known fix for check `id` if the check currently fails or warns.
+/// With `dry_run`, only report what would change. The previous value is kept in an undo journal for `revert_fixes`.
+#[tauri::command]
+pub async fn fix_check(
+    app: AppHandle,
+    state: State<'_, ScanState>,
+    id: String,
+    dry_run: bool,
+) -> Result<FixOutcome, String> {
+    let policy = state.policy()?;
+    let locale = state.locale();
+    let journal = journal(&app)?;
+    tokio::task::spawn_blocking(move || {
+        let checks = CheckRegistry::with_policy(&policy);
+        let ctx = Arc::new(
+            ScanContext::system()
+                .with_policy(Arc::clone(&policy))
+                .with_locale(locale),
+        );
+        let mut reg = live_registry()?;
+        let change = fix::fix_check(&checks, &ctx, &mut reg, &journal, &id, dry_run)
+            .map_err(|e| e.to_string())?;
+        let result = if dry_run {
+            None
+        } else {
+            Some(rerun(&checks, &policy, locale, &id)?)
+        };
+        Ok(FixOutcome { change, result })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Undo every fix in the journal, newest first, and rerun the affected checks.
+#[tauri::command]
+pub async fn revert_fixes(
+    app: AppHandle,
+    state: State<'_, ScanState>,
+) -> Result<Vec<CheckResult>, String> {
+    let policy = state.policy()?;
+    let locale = state.locale();
+    let journal = journal(&app)?;
+    tokio::task::spawn_blocking(move || {
+        let mut reg = live_registry()?;
+        let reverted = fix::revert_all(&mut reg, &journal).map_err(|e| e.to_string())?;
+        let checks = CheckRegistry::with_policy(&policy);
+        let mut ids: Vec<&str> = Vec::new();
+        for entry in &reverted {
+            if !ids.contains(&entry.check_id.as_str()) {
+                ids.push(&entry.check_id);
+            }
+        }
+        ids.into_iter()
+            .filter(|id| checks.get(id).is_some())
+            .map(|id| rerun(&checks, &policy, locale, id))
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+fn journal(app: &AppHandle) -> Result<Journal, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Cannot locate app data directory: {}", e))?;
+    Ok(Journal::in_dir(&dir))
+}
+
+/// Rerun `id` on a fresh context, so it sees the registry as it is now.
+fn rerun(
+    checks: &CheckRegistry,
+    policy: &Arc<Policy>,
+    locale: Locale,
+    id: &str,
+) -> Result<CheckResult, String> {
+    let ctx = Arc::new(
+        ScanContext::system()
+            .with_policy(Arc::clone(policy))
+            .with_locale(locale),
+    );
+    crate::checks::run_check(checks, id, &ctx).map_err(|e| e.to_string())
+}
+
+#[cfg(windows)]
+fn live_registry() -> Result<crate::checks::registry::Win32Registry, String> {
+    Ok(crate::checks::registry::Win32Registry)
+}
+
+#[cfg(not(windows))]
+fn live_registry() -> Result<crate::checks::registry::FakeRegistry, String> {
+    Err("Fixes can only be applied on Windows".into())
+}
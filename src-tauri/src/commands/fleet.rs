@@ -0,0 +1,42 @@
+use std::path::{Path, PathBuf};
+
+use tauri::State;
+
+use crate::report::fleet::{self, Fleet, FleetFormat, FleetSummary};
+
+use super::checks::ScanState;
+
+/// Summarize the scans in `folder`, one JSON report per machine, and write
+/// the summary to `path`. Returns how many machines it covers.
+#[tauri::command]
+pub async fn summarize_fleet(
+    state: State<'_, ScanState>,
+    folder: String,
+    path: String,
+    format: Option<FleetFormat>,
+) -> Result<usize, String> {
+    let locale = state.locale();
+    // Reading a share full of reports can take a while; keep it off the
+    // async runtime.
+    tokio::task::spawn_blocking(move || {
+        let fleet = Fleet::load_dir(Path::new(&folder))?;
+        if fleet.machines.is_empty() {
+            return Err(format!("No scan results found in {}", folder));
+        }
+        let format = format.unwrap_or_default();
+        let summary = FleetSummary::new(&fleet);
+        let rendered = fleet::render(&summary, format, locale)?;
+
+        let mut path = PathBuf::from(path);
+        let matches = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case(format.extension()));
+        if !matches {
+            path.set_extension(format.extension());
+        }
+        std::fs::write(&path, rendered).map_err(|e| format!("Failed to write summary: {}", e))?;
+        Ok(summary.machines)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
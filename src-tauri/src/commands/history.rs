@@ -0,0 +1,54 @@
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::checks::history::{History, ScanDiff, ScanSummary};
+use crate::types::ScanResult;
+
+/// Past scans on this machine, newest first.
+#[tauri::command]
+pub async fn list_scans(app: AppHandle) -> Result<Vec<ScanSummary>, String> {
+    let history = history(&app)?;
+    tokio::task::spawn_blocking(move || history.list().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn get_scan(app: AppHandle, id: String) -> Result<ScanResult, String> {
+    let history = history(&app)?;
+    tokio::task::spawn_blocking(move || history.get(&id).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Checks whose status or message changed from scan `a` to scan `b`.
+#[tauri::command]
+pub async fn diff_scans(app: AppHandle, a: String, b: String) -> Result<ScanDiff, String> {
+    let history = history(&app)?;
+    tokio::task::spawn_blocking(move || history.diff(&a, &b).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Add a live scan to the history, off the async runtime. The scan itself
+/// succeeded, so failing to store it does not fail the scan; the error is
+/// logged and sent to the frontend as a `history://record-failed` event.
+pub async fn record(app: &AppHandle, result: ScanResult) {
+    let outcome = match history(app) {
+        Ok(history) => tokio::task::spawn_blocking(move || history.record(&result).map_err(|e| e.to_string()))
+            .await
+            .unwrap_or_else(|e| Err(format!("Task join error: {}", e))),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = outcome {
+        eprintln!("Failed to record the scan in the history: {}", e);
+        let _ = app.emit("history://record-failed", e);
+    }
+}
+
+fn history(app: &AppHandle) -> Result<History, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Cannot locate app data directory: {}", e))?;
+    Ok(History::in_dir(&dir))
+}
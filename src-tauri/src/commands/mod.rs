@@ -1,2 +1,6 @@
 pub mod checks;
+pub mod fix;
+pub mod fleet;
+pub mod history;
 pub mod report;
+pub mod snapshot;
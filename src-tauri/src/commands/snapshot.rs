@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use tauri::{AppHandle, State};
+
+use crate::checks::snapshot::{self, Snapshot};
+use crate::checks::{CheckRegistry, ScanContext};
+use crate::types::ScanResult;
+
+use super::checks::{check_timeout, ScanState};
+
+/// Run a live scan and write everything it read to a JSON snapshot at `path`.
+/// The scan is added to the history like any other live scan, and counts as
+/// the scan in flight for `cancel_scan`.
+#[tauri::command]
+pub async fn capture_snapshot(
+    app: AppHandle,
+    state: State<'_, ScanState>,
+    path: String,
+    timeout_secs: Option<u64>,
+) -> Result<ScanResult, String> {
+    let timeout = check_timeout(timeout_secs)?;
+    let policy = state.policy()?;
+    let locale = state.locale();
+    let cancel = state.begin_scan()?;
+
+    let result = tokio::task::spawn_blocking(move || {
+        let checks = CheckRegistry::with_policy(&policy);
+        let ctx = ScanContext::system()
+            .with_timeout(timeout)
+            .with_cancel_token(cancel)
+            .with_policy(policy)
+            .with_locale(locale);
+        let snapshot = snapshot::capture(&checks, ctx).map_err(|e| e.to_string())?;
+        snapshot
+            .save(&PathBuf::from(path))
+            .map_err(|e| e.to_string())?;
+        Ok::<_, String>(snapshot.result)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e));
+
+    state.end_scan();
+    let result = result??;
+    super::history::record(&app, result.clone()).await;
+    Ok(result)
+}
+
+/// Rerun the checks offline against a snapshot written by `capture_snapshot`,
+/// under the policy recorded in the snapshot.
+#[tauri::command]
+pub async fn replay_snapshot(
+    state: State<'_, ScanState>,
+    path: String,
+) -> Result<ScanResult, String> {
+    let locale = state.locale();
+    tokio::task::spawn_blocking(move || {
+        let snapshot = Snapshot::load(&PathBuf::from(path)).map_err(|e| e.to_string())?;
+        let checks = CheckRegistry::with_policy(&snapshot.policy);
+        snapshot::replay(&checks, &snapshot, locale).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::checks::Check;
+use crate::types::{CheckInfo, RemediationStep};
+
+/// Named values substituted for `{name}` placeholders in a catalog entry.
+pub type Params<'a> = &'a [(&'a str, &'a dyn fmt::Display)];
+
+/// Language of check messages, remediation and the report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Nl,
+    De,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::Nl, Locale::De];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Nl => "nl",
+            Locale::De => "de",
+        }
+    }
+
+    /// Parse a language tag such as "nl", "de-DE" or "nl_BE.UTF-8".
+    pub fn from_code(tag: &str) -> Option<Self> {
+        let language = tag.split(['-', '_', '.']).next()?.to_ascii_lowercase();
+        Self::ALL.into_iter().find(|l| l.code() == language)
+    }
+
+    /// The Windows display language, or English if it has no catalog.
+    #[cfg(windows)]
+    pub fn system() -> Self {
+        use windows::Win32::Globalization::GetUserDefaultUILanguage;
+
+        const LANG_GERMAN: u16 = 0x07;
+        const LANG_DUTCH: u16 = 0x13;
+        // The low ten bits of a LANGID are the primary language.
+        match unsafe { GetUserDefaultUILanguage() } & 0x3ff {
+            LANG_DUTCH => Locale::Nl,
+            LANG_GERMAN => Locale::De,
+            _ => Locale::En,
+        }
+    }
+
+    #[cfg(not(windows))]
+    pub fn system() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_code(&value))
+            .unwrap_or_default()
+    }
+
+    /// The catalog entry for `key` with `params` filled in. Falls back to the
+    /// English entry, then to the key itself, so a missing translation never
+    /// hides a message.
+    pub fn t(self, key: &str, params: Params) -> String {
+        let template = self
+            .lookup(key)
+            .or_else(|| Locale::En.lookup(key))
+            .unwrap_or(key);
+        let mut text = template.to_string();
+        for (name, value) in params {
+            text = text.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+        text
+    }
+
+    pub fn lookup(self, key: &str) -> Option<&'static str> {
+        catalogs()[self as usize].get(key).map(String::as_str)
+    }
+
+    /// Translated label of a built-in check; policy rules keep their own label.
+    pub fn check_label(self, check: &dyn Check) -> String {
+        self.lookup(&format!("{}.label", check.id()))
+            .map_or_else(|| check.label().to_string(), str::to_string)
+    }
+
+    pub fn check_info(self, check: &dyn Check) -> CheckInfo {
+        CheckInfo {
+            label: self.check_label(check),
+            ..check.info()
+        }
+    }
+
+    pub fn instruction(self, key: &str, params: Params) -> RemediationStep {
+        RemediationStep::instruction(self.t(key, params))
+    }
+
+    /// Plain-text form of `step`, for output that cannot render structure.
+    pub fn step_text(self, step: &RemediationStep) -> String {
+        match step {
+            RemediationStep::Instruction { text } => text.clone(),
+            RemediationStep::MenuPath { path } => {
+                self.t("remediation.go_to", &[("path", &path.join(" \u{2192} "))])
+            }
+            RemediationStep::Link { label, url } => format!("{}: {}", label, url),
+            RemediationStep::Command { command, description } => {
+                format!("{}: {}", description, command)
+            }
+            RemediationStep::AutoFix { description, .. } => {
+                self.t("remediation.auto_fix", &[("description", description)])
+            }
+        }
+    }
+
+    /// A menu path whose items are catalog keys, e.g. `["menu.file", "menu.options"]`.
+    pub fn menu(self, keys: &[&str]) -> RemediationStep {
+        RemediationStep::MenuPath {
+            path: keys.iter().map(|key| self.t(key, &[])).collect(),
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// Catalogs indexed like [`Locale::ALL`].
+fn catalogs() -> &'static [HashMap<String, String>; 3] {
+    static CATALOGS: OnceLock<[HashMap<String, String>; 3]> = OnceLock::new();
+    CATALOGS.get_or_init(|| {
+        let parse = |json: &str| serde_json::from_str(json).expect("built-in catalog is valid JSON");
+        [
+            parse(include_str!("../locales/en.json")),
+            parse(include_str!("../locales/nl.json")),
+            parse(include_str!("../locales/de.json")),
+        ]
+    })
+}
@@ -1,14 +1,29 @@
+mod cli;
 mod commands;
 mod checks;
+mod i18n;
+mod report;
 mod types;
 
-use commands::{checks as check_cmds, report};
+use checks::policy::Policy;
+use i18n::Locale;
+use commands::{checks as check_cmds, fix, fleet, history, report as report_cmds, snapshot};
 use tauri::Manager;
 
+/// Headless mode: run the CLI command in `args` (without the program name)
+/// and return the process exit code.
+pub fn run_cli(args: &[String]) -> i32 {
+    cli::main(args)
+}
+
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
+        .manage(check_cmds::ScanState::new(
+            Policy::load_configured().map_err(|e| e.to_string()),
+            Locale::system(),
+        ))
         .setup(|app| {
             let _webview = app.get_webview_window("main")
                 .expect("no main window");
@@ -22,9 +37,21 @@ pub fn run() {
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
+            check_cmds::list_checks,
             check_cmds::run_readiness_scan,
-            report::generate_report,
-            report::save_report,
+            check_cmds::cancel_scan,
+            check_cmds::get_locale,
+            check_cmds::set_locale,
+            fix::fix_check,
+            fix::revert_fixes,
+            fleet::summarize_fleet,
+            history::list_scans,
+            history::get_scan,
+            history::diff_scans,
+            report_cmds::generate_report,
+            report_cmds::save_report,
+            snapshot::capture_snapshot,
+            snapshot::replay_snapshot,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
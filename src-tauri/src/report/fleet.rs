@@ -0,0 +1,561 @@
+//! Summary across many machines, built from the JSON reports a deployment
+//! collected into one folder: which checks hold the migration up, where, and
+//! on what Windows and Office versions.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::i18n::Locale;
+use crate::types::{CheckResult, CheckStatus, ScanResult, Severity};
+
+use super::json::{JsonReport, SCHEMA_VERSION};
+use super::template;
+
+/// Failure messages listed in the summary, most frequent first.
+pub const TOP_FAILURES: usize = 10;
+
+const OS_CHECK: &str = "windows_os";
+const ONENOTE_CHECK: &str = "onenote";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FleetFormat {
+    #[default]
+    Markdown,
+    Html,
+    /// One row per count, for pivoting in a spreadsheet.
+    Csv,
+}
+
+impl FleetFormat {
+    pub const ALL: [FleetFormat; 3] = [FleetFormat::Markdown, FleetFormat::Html, FleetFormat::Csv];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FleetFormat::Markdown => "markdown",
+            FleetFormat::Html => "html",
+            FleetFormat::Csv => "csv",
+        }
+    }
+
+    /// Accepts [`Self::name`] or the file extension, e.g. "md".
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|f| f.name() == name || f.extension() == name)
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            FleetFormat::Markdown => "md",
+            FleetFormat::Html => "html",
+            FleetFormat::Csv => "csv",
+        }
+    }
+}
+
+/// A scan and the machine it ran on.
+#[derive(Debug, Clone)]
+pub struct MachineScan {
+    pub machine: String,
+    pub scan: ScanResult,
+}
+
+/// A file in the folder that is not a scan, or one this version cannot read.
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedFile {
+    pub file: String,
+    pub reason: String,
+}
+
+/// The scans in a folder, one `.json` file per machine named after it, e.g.
+/// `PC-0042.json`. Both JSON reports and bare scan results are read.
+#[derive(Debug, Clone, Default)]
+pub struct Fleet {
+    pub machines: Vec<MachineScan>,
+    pub skipped: Vec<SkippedFile>,
+}
+
+impl Fleet {
+    pub fn load_dir(dir: &Path) -> Result<Self, String> {
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read folder {}: {}", dir.display(), e))?;
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+            })
+            .collect();
+        paths.sort();
+
+        let mut fleet = Fleet::default();
+        for path in paths {
+            let file = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            match read_scan(&path) {
+                Ok(scan) => fleet.machines.push(MachineScan {
+                    machine: path
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    scan,
+                }),
+                Err(reason) => fleet.skipped.push(SkippedFile { file, reason }),
+            }
+        }
+        Ok(fleet)
+    }
+}
+
+fn read_scan(path: &Path) -> Result<ScanResult, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read: {}", e))?;
+    let value: Value = serde_json::from_str(&text).map_err(|e| format!("Not JSON: {}", e))?;
+    if value.get("scan").is_some() {
+        let report: JsonReport =
+            serde_json::from_value(value).map_err(|e| format!("Not a readiness report: {}", e))?;
+        if report.schema_version > SCHEMA_VERSION {
+            return Err(format!(
+                "Report schema version {} is newer than this tool supports ({})",
+                report.schema_version, SCHEMA_VERSION
+            ));
+        }
+        Ok(report.scan)
+    } else {
+        serde_json::from_value(value).map_err(|e| format!("Not a scan result: {}", e))
+    }
+}
+
+/// Machines per status.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StatusCounts {
+    pub pass: usize,
+    pub warning: usize,
+    pub fail: usize,
+    pub skipped: usize,
+    pub timeout: usize,
+}
+
+impl StatusCounts {
+    fn add(&mut self, status: &CheckStatus) {
+        match status {
+            CheckStatus::Pass => self.pass += 1,
+            CheckStatus::Warning => self.warning += 1,
+            CheckStatus::Fail => self.fail += 1,
+            CheckStatus::Skipped => self.skipped += 1,
+            CheckStatus::Timeout => self.timeout += 1,
+        }
+    }
+
+    fn iter(&self) -> [(CheckStatus, usize); 5] {
+        [
+            (CheckStatus::Pass, self.pass),
+            (CheckStatus::Warning, self.warning),
+            (CheckStatus::Fail, self.fail),
+            (CheckStatus::Skipped, self.skipped),
+            (CheckStatus::Timeout, self.timeout),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckSummary {
+    pub id: String,
+    /// The label as the first machine reported it, in that scan's language.
+    pub label: String,
+    pub severity: Severity,
+    pub counts: StatusCounts,
+    /// Machines where the check failed or timed out.
+    pub blocked: Vec<String>,
+}
+
+/// One message reported by the same check on several machines.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailureSummary {
+    pub check_id: String,
+    pub label: String,
+    pub status: CheckStatus,
+    pub message: String,
+    pub count: usize,
+    pub machines: Vec<String>,
+}
+
+/// How many machines run one version. `None` when a scan did not record it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Share {
+    pub value: Option<String>,
+    pub count: usize,
+    pub percent: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetSummary {
+    pub generated: String,
+    pub machines: usize,
+    /// Machines per overall verdict.
+    pub overall: StatusCounts,
+    /// Over the machines that have a score; `None` if none has.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_score: Option<u32>,
+    /// In the order the checks first appear in the scans.
+    pub checks: Vec<CheckSummary>,
+    /// Failed, timed-out and warning checks grouped by message, at most
+    /// [`TOP_FAILURES`].
+    pub top_failures: Vec<FailureSummary>,
+    pub os_builds: Vec<Share>,
+    pub office_versions: Vec<Share>,
+    pub skipped_files: Vec<SkippedFile>,
+}
+
+impl FleetSummary {
+    pub fn new(fleet: &Fleet) -> Self {
+        let mut overall = StatusCounts::default();
+        let mut checks: Vec<CheckSummary> = Vec::new();
+        let mut failures: Vec<FailureSummary> = Vec::new();
+        let mut os_builds = Vec::new();
+        let mut office_versions = Vec::new();
+
+        for MachineScan { machine, scan } in &fleet.machines {
+            overall.add(&scan.overall);
+            os_builds.push(find_check(scan, OS_CHECK).and_then(os_build));
+            office_versions.push(find_check(scan, ONENOTE_CHECK).and_then(office_version));
+
+            for check in &scan.checks {
+                let index = match checks.iter().position(|c| c.id == check.id) {
+                    Some(index) => index,
+                    None => {
+                        checks.push(CheckSummary {
+                            id: check.id.clone(),
+                            label: check.label.clone(),
+                            severity: check.severity,
+                            counts: StatusCounts::default(),
+                            blocked: Vec::new(),
+                        });
+                        checks.len() - 1
+                    }
+                };
+                let summary = &mut checks[index];
+                summary.counts.add(&check.status);
+                if matches!(check.status, CheckStatus::Fail | CheckStatus::Timeout) {
+                    summary.blocked.push(machine.clone());
+                }
+
+                if matches!(check.status, CheckStatus::Pass | CheckStatus::Skipped) {
+                    continue;
+                }
+                let same = |f: &&mut FailureSummary| {
+                    f.check_id == check.id && f.status == check.status && f.message == check.message
+                };
+                match failures.iter_mut().find(same) {
+                    Some(failure) => {
+                        failure.count += 1;
+                        failure.machines.push(machine.clone());
+                    }
+                    None => failures.push(FailureSummary {
+                        check_id: check.id.clone(),
+                        label: check.label.clone(),
+                        status: check.status.clone(),
+                        message: check.message.clone(),
+                        count: 1,
+                        machines: vec![machine.clone()],
+                    }),
+                }
+            }
+        }
+
+        // Stable, so equally common messages keep the order they were met in.
+        failures.sort_by_key(|f| std::cmp::Reverse(f.count));
+        failures.truncate(TOP_FAILURES);
+
+        let machines = fleet.machines.len();
+        let scores: Vec<u32> = fleet
+            .machines
+            .iter()
+            .filter_map(|m| m.scan.score.map(u32::from))
+            .collect();
+        Self {
+            generated: chrono::Local::now().to_rfc3339(),
+            machines,
+            overall,
+            average_score: match scores.len() {
+                0 => None,
+                n => Some(scores.iter().sum::<u32>() / n as u32),
+            },
+            checks,
+            top_failures: failures,
+            os_builds: shares(os_builds),
+            office_versions: shares(office_versions),
+            skipped_files: fleet.skipped.clone(),
+        }
+    }
+}
+
+fn find_check<'a>(scan: &'a ScanResult, id: &str) -> Option<&'a CheckResult> {
+    scan.checks.iter().find(|c| c.id == id)
+}
+
+fn evidence<'a>(check: &'a CheckResult, key: &str) -> Option<&'a str> {
+    check
+        .evidence
+        .iter()
+        .find(|e| e.key == key)
+        .map(|e| e.value.as_str())
+}
+
+/// E.g. "22631.4890", from the Windows check's `CurrentBuild` and `UBR`.
+fn os_build(check: &CheckResult) -> Option<String> {
+    let build = evidence(check, "CurrentBuild")?;
+    Some(match evidence(check, "UBR") {
+        Some(ubr) => format!("{}.{}", build, ubr),
+        None => build.to_string(),
+    })
+}
+
+/// The Click-to-Run version, e.g. "16.0.17328.20162", or for MSI installs
+/// the version in the `InstallRoot` key path, e.g. "16.0".
+fn office_version(check: &CheckResult) -> Option<String> {
+    if let Some(version) = evidence(check, "VersionToReport") {
+        return Some(version.to_string());
+    }
+    let install = check.evidence.iter().find(|e| e.key == "Path")?;
+    let mut parts = install.source.split('\\');
+    parts.find(|p| p.eq_ignore_ascii_case("Office"))?;
+    parts
+        .next()
+        .filter(|v| v.contains('.') && v.chars().all(|c| c.is_ascii_digit() || c == '.'))
+        .map(String::from)
+}
+
+/// Distinct values, most common first, then by value.
+fn shares(values: Vec<Option<String>>) -> Vec<Share> {
+    let total = values.len();
+    let mut counts: HashMap<Option<String>, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value).or_default() += 1;
+    }
+    let mut shares: Vec<Share> = counts
+        .into_iter()
+        .map(|(value, count)| Share {
+            value,
+            count,
+            percent: (count * 100 / total.max(1)) as u32,
+        })
+        .collect();
+    shares.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    shares
+}
+
+/// What the fleet templates see: the summary and the language it is in.
+#[derive(Serialize)]
+struct FleetContext<'a> {
+    locale: Locale,
+    #[serde(flatten)]
+    summary: &'a FleetSummary,
+}
+
+/// `locale` translates the headings; messages and labels stay in the
+/// language each machine scanned in.
+pub fn render(summary: &FleetSummary, format: FleetFormat, locale: Locale) -> Result<String, String> {
+    let context = FleetContext { locale, summary };
+    match format {
+        FleetFormat::Markdown => template::render_context("fleet.md", locale, context),
+        FleetFormat::Html => template::render_context("fleet.html", locale, context),
+        FleetFormat::Csv => Ok(csv(summary)),
+    }
+}
+
+/// Long format: `section` says what a row counts, `value` what was counted.
+/// `machines` is filled in for blocked and failure rows, and version rows
+/// leave `value` empty when the scans did not record one.
+fn csv(summary: &FleetSummary) -> String {
+    let mut out = String::from("section,check,label,value,count,machines\r\n");
+    let mut row = |fields: [&str; 6]| {
+        let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    };
+
+    for (status, count) in summary.overall.iter() {
+        row(["overall", "", "", status_name(&status), &count.to_string(), ""]);
+    }
+    for check in &summary.checks {
+        for (status, count) in check.counts.iter() {
+            row(["status", &check.id, &check.label, status_name(&status), &count.to_string(), ""]);
+        }
+        row(["blocked", &check.id, &check.label, "", &check.blocked.len().to_string(), &check.blocked.join(";")]);
+    }
+    for failure in &summary.top_failures {
+        row([
+            "failure",
+            &failure.check_id,
+            &failure.label,
+            &failure.message,
+            &failure.count.to_string(),
+            &failure.machines.join(";"),
+        ]);
+    }
+    for (section, shares) in [("os_build", &summary.os_builds), ("office_version", &summary.office_versions)] {
+        for share in shares {
+            row([section, "", "", share.value.as_deref().unwrap_or(""), &share.count.to_string(), ""]);
+        }
+    }
+    out
+}
+
+fn status_name(status: &CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Pass => "pass",
+        CheckStatus::Warning => "warning",
+        CheckStatus::Fail => "fail",
+        CheckStatus::Skipped => "skipped",
+        CheckStatus::Timeout => "timeout",
+    }
+}
+
+/// Quote as RFC 4180 asks, and keep spreadsheets from running a value that
+/// looks like a formula.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::fixtures::temp_dir;
+
+    fn scan(status: CheckStatus, message: &str) -> ScanResult {
+        ScanResult {
+            checks: vec![CheckResult {
+                id: "onenote".into(),
+                label: "OneNote Desktop".into(),
+                status: status.clone(),
+                message: message.into(),
+                weight: 3,
+                ..Default::default()
+            }],
+            timestamp: "2024-05-02T08:14:31+00:00".into(),
+            duration_ms: 2400,
+            os_info: "Windows 11 Pro 23H2 (Build 22631.2861)".into(),
+            overall: status,
+            score: None,
+            locale: Locale::En,
+            redacted: false,
+            inventory: None,
+        }
+    }
+
+    fn write_json(dir: &Path, file: &str, value: &impl Serialize) {
+        std::fs::write(dir.join(file), serde_json::to_string(value).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn load_dir_reads_reports_and_skips_everything_else() {
+        let dir = temp_dir("fleet-load");
+        write_json(&dir, "PC-0001.json", &JsonReport::new(&scan(CheckStatus::Pass, "ok")));
+        write_json(&dir, "PC-0002.JSON", &scan(CheckStatus::Fail, "missing"));
+        let mut future = JsonReport::new(&scan(CheckStatus::Pass, "ok"));
+        future.schema_version = SCHEMA_VERSION + 1;
+        write_json(&dir, "future.json", &future);
+        write_json(&dir, "settings.json", &serde_json::json!({ "theme": "dark" }));
+        std::fs::write(dir.join("broken.json"), "{ \"scan\": ").unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a report").unwrap();
+        std::fs::create_dir(dir.join("archive.json")).unwrap();
+
+        let fleet = Fleet::load_dir(&dir).unwrap();
+        let machines: Vec<_> = fleet.machines.iter().map(|m| m.machine.as_str()).collect();
+        assert_eq!(machines, ["PC-0001", "PC-0002"]);
+        assert_eq!(fleet.machines[1].scan.overall, CheckStatus::Fail);
+
+        let skipped: Vec<_> = fleet.skipped.iter().map(|s| s.file.as_str()).collect();
+        assert_eq!(skipped, ["broken.json", "future.json", "settings.json"]);
+        assert!(fleet.skipped[0].reason.starts_with("Not JSON"));
+        assert!(fleet.skipped[1].reason.contains("newer than this tool supports"));
+        assert!(fleet.skipped[2].reason.starts_with("Not a scan result"));
+    }
+
+    #[test]
+    fn load_dir_fails_for_a_missing_folder() {
+        let dir = temp_dir("fleet-missing").join("nowhere");
+        assert!(Fleet::load_dir(&dir).unwrap_err().starts_with("Failed to read folder"));
+    }
+
+    #[test]
+    fn average_score_leaves_out_unscored_machines() {
+        let machine = |name: &str, score| {
+            let mut scan = scan(CheckStatus::Pass, "ok");
+            scan.score = score;
+            MachineScan { machine: name.into(), scan }
+        };
+        let mut fleet = Fleet {
+            machines: vec![
+                machine("PC-0001", Some(90)),
+                machine("PC-0002", None),
+                machine("PC-0003", Some(61)),
+            ],
+            skipped: Vec::new(),
+        };
+        assert_eq!(FleetSummary::new(&fleet).average_score, Some(75));
+
+        fleet.machines.retain(|m| m.scan.score.is_none());
+        let summary = FleetSummary::new(&fleet);
+        assert_eq!(summary.average_score, None);
+        let markdown = render(&summary, FleetFormat::Markdown, Locale::En).unwrap();
+        assert!(!markdown.contains("Average readiness score"), "{}", markdown);
+    }
+
+    #[test]
+    fn csv_fields_cannot_run_as_formulas() {
+        assert_eq!(csv_field("=HYPERLINK(\"http://x\")"), "\"'=HYPERLINK(\"\"http://x\"\")\"");
+        assert_eq!(csv_field("+1"), "'+1");
+        assert_eq!(csv_field("-2"), "'-2");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_field("PC-0001"), "PC-0001");
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[test]
+    fn csv_escapes_messages_and_machine_names() {
+        let fleet = Fleet {
+            machines: vec![
+                MachineScan {
+                    machine: "=cmd|'/c calc'!A1".into(),
+                    scan: scan(CheckStatus::Fail, "@risk, \"quoted\""),
+                },
+                MachineScan {
+                    machine: "PC-0002".into(),
+                    scan: scan(CheckStatus::Fail, "@risk, \"quoted\""),
+                },
+            ],
+            skipped: Vec::new(),
+        };
+        let csv = render(&FleetSummary::new(&fleet), FleetFormat::Csv, Locale::En).unwrap();
+        let rows: Vec<_> = csv.split("\r\n").collect();
+        assert_eq!(rows[0], "section,check,label,value,count,machines");
+        assert!(rows.contains(&"blocked,onenote,OneNote Desktop,,2,'=cmd|'/c calc'!A1;PC-0002"));
+        assert!(rows.contains(
+            &"failure,onenote,OneNote Desktop,\"'@risk, \"\"quoted\"\"\",2,'=cmd|'/c calc'!A1;PC-0002"
+        ));
+        assert!(rows.iter().all(|row| !row.starts_with(['=', '+', '-', '@'])));
+    }
+}
@@ -0,0 +1,321 @@
+//! Machine-readable report for central collection. Consumers should key on
+//! check ids, which are stable, rather than on labels, which are translated
+//! and may be reworded.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::checks::CheckRegistry;
+use crate::i18n::Locale;
+use crate::types::{CheckCategory, CheckStatus, ScanResult, Severity};
+
+/// Bumped on changes that could break a consumer, such as a renamed or
+/// removed field. New optional fields keep the version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub const SCHEMA_ID: &str =
+    "https://raw.githubusercontent.com/dimaginar/onenote-to-joplin/main/schemas/readiness-report.v1.schema.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonReport {
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    pub schema_version: u32,
+    pub tool: ToolInfo,
+    /// Every built-in check, whether or not it ran in this scan.
+    pub catalog: Vec<CatalogEntry>,
+    pub scan: ScanResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolInfo {
+    pub name: String,
+    pub version: String,
+}
+
+/// A built-in check as consumers should identify it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogEntry {
+    pub id: String,
+    pub category: CheckCategory,
+    pub severity: Severity,
+    pub weight: u32,
+    pub depends_on: Vec<String>,
+}
+
+impl JsonReport {
+    pub fn new(scan: &ScanResult) -> Self {
+        Self {
+            schema: SCHEMA_ID.into(),
+            schema_version: SCHEMA_VERSION,
+            tool: ToolInfo {
+                name: env!("CARGO_PKG_NAME").into(),
+                version: env!("CARGO_PKG_VERSION").into(),
+            },
+            catalog: catalog(),
+            scan: scan.clone(),
+        }
+    }
+}
+
+pub fn catalog() -> Vec<CatalogEntry> {
+    CheckRegistry::builtin()
+        .iter()
+        .map(|check| {
+            let info = check.info();
+            CatalogEntry {
+                id: info.id,
+                category: info.category,
+                severity: info.severity,
+                weight: info.weight,
+                depends_on: info.depends_on,
+            }
+        })
+        .collect()
+}
+
+/// JSON Schema (draft 2020-12) of [`JsonReport`]. The copy in `schemas/` is
+/// generated from this with `onenote-to-joplin schema`.
+pub fn schema() -> Value {
+    let builtin_ids: Vec<String> = catalog().into_iter().map(|c| c.id).collect();
+    let step = |kind: &str, fields: &[&str]| {
+        let mut properties = serde_json::Map::new();
+        properties.insert("kind".into(), json!({ "const": kind }));
+        for &field in fields {
+            let ty = match field {
+                "path" => json!({ "type": "array", "items": { "type": "string" } }),
+                _ => json!({ "type": "string" }),
+            };
+            properties.insert(field.into(), ty);
+        }
+        let mut required = vec!["kind"];
+        required.extend_from_slice(fields);
+        json!({ "type": "object", "required": required, "properties": properties })
+    };
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": SCHEMA_ID,
+        "title": "OneNote to Joplin readiness report",
+        "description": format!("Report schema version {}. Fields may be added without a version change.", SCHEMA_VERSION),
+        "type": "object",
+        "required": ["schemaVersion", "tool", "catalog", "scan"],
+        "properties": {
+            "$schema": { "type": "string" },
+            "schemaVersion": { "const": SCHEMA_VERSION },
+            "tool": {
+                "type": "object",
+                "required": ["name", "version"],
+                "properties": {
+                    "name": { "type": "string" },
+                    "version": { "type": "string", "description": "Version of the tool that ran the scan." }
+                }
+            },
+            "catalog": { "type": "array", "items": { "$ref": "#/$defs/catalogEntry" } },
+            "scan": { "$ref": "#/$defs/scanResult" }
+        },
+        "$defs": {
+            "builtinCheckId": {
+                "description": "Ids of the checks shipped with the tool. They do not change when labels are reworded or translated.",
+                "enum": builtin_ids
+            },
+            "checkId": {
+                "description": "A built-in check id, or the id of a rule from the policy file.",
+                "type": "string",
+                "minLength": 1
+            },
+            "status": { "enum": names(&[
+                CheckStatus::Pass,
+                CheckStatus::Fail,
+                CheckStatus::Warning,
+                CheckStatus::Skipped,
+                CheckStatus::Timeout,
+            ]) },
+            "severity": { "enum": names(&[Severity::Blocker, Severity::Major, Severity::Minor, Severity::Info]) },
+            "category": { "enum": names(&[
+                CheckCategory::Joplin,
+                CheckCategory::System,
+                CheckCategory::Office,
+                CheckCategory::Sync,
+                CheckCategory::Policy,
+            ]) },
+            "locale": { "enum": names(&Locale::ALL) },
+            "catalogEntry": {
+                "type": "object",
+                "required": ["id", "category", "severity", "weight", "dependsOn"],
+                "properties": {
+                    "id": { "$ref": "#/$defs/builtinCheckId" },
+                    "category": { "$ref": "#/$defs/category" },
+                    "severity": { "$ref": "#/$defs/severity" },
+                    "weight": { "type": "integer", "minimum": 0 },
+                    "dependsOn": { "type": "array", "items": { "$ref": "#/$defs/builtinCheckId" } }
+                }
+            },
+            "evidence": {
+                "type": "object",
+                "required": ["key", "value", "source"],
+                "properties": {
+                    "key": { "type": "string" },
+                    "value": { "type": "string" },
+                    "source": { "type": "string" }
+                }
+            },
+            "remediationStep": {
+                "oneOf": [
+                    step("instruction", &["text"]),
+                    step("menuPath", &["path"]),
+                    step("link", &["label", "url"]),
+                    step("command", &["command", "description"]),
+                    step("autoFix", &["checkId", "description"])
+                ]
+            },
+            "checkResult": {
+                "type": "object",
+                "required": ["id", "label", "status", "message", "remediation", "evidence", "durationMs", "severity", "weight"],
+                "properties": {
+                    "id": { "$ref": "#/$defs/checkId" },
+                    "label": { "type": "string", "description": "Display name in the scan's locale." },
+                    "status": { "$ref": "#/$defs/status" },
+                    "message": { "type": "string" },
+                    "remediation": { "type": "array", "items": { "$ref": "#/$defs/remediationStep" } },
+                    "evidence": { "type": "array", "items": { "$ref": "#/$defs/evidence" } },
+                    "durationMs": { "type": "integer", "minimum": 0 },
+                    "severity": { "$ref": "#/$defs/severity" },
+                    "weight": { "type": "integer", "minimum": 0 }
+                }
+            },
+            "page": {
+                "type": "object",
+                "required": ["id", "name"],
+                "properties": {
+                    "id": { "type": "string" },
+                    "name": { "type": "string" },
+                    "lastModified": { "type": "string", "format": "date-time" }
+                }
+            },
+            "section": {
+                "type": "object",
+                "required": ["id", "name", "path", "pages"],
+                "properties": {
+                    "id": { "type": "string" },
+                    "name": { "type": "string" },
+                    "path": { "type": "string" },
+                    "lastModified": { "type": "string", "format": "date-time" },
+                    "isLocked": { "type": "boolean", "description": "Password-protected and locked. Absent means false." },
+                    "encrypted": { "type": "boolean", "description": "Password-protected. Absent means false." },
+                    "readOnly": { "type": "boolean", "description": "OneNote cannot write to the section. Absent means false." },
+                    "pages": { "type": "array", "items": { "$ref": "#/$defs/page" } }
+                }
+            },
+            "sectionGroup": {
+                "description": "A notebook or a section group in it.",
+                "type": "object",
+                "required": ["id", "name", "path", "sectionGroups", "sections"],
+                "properties": {
+                    "id": { "type": "string" },
+                    "name": { "type": "string" },
+                    "path": { "type": "string" },
+                    "lastModified": { "type": "string", "format": "date-time" },
+                    "sectionGroups": { "type": "array", "items": { "$ref": "#/$defs/sectionGroup" } },
+                    "sections": { "type": "array", "items": { "$ref": "#/$defs/section" } }
+                }
+            },
+            "inventory": {
+                "type": "object",
+                "required": ["notebooks"],
+                "properties": {
+                    "notebooks": { "type": "array", "items": { "$ref": "#/$defs/sectionGroup" } }
+                }
+            },
+            "scanResult": {
+                "type": "object",
+                "required": ["checks", "timestamp", "osInfo", "overall", "locale"],
+                "properties": {
+                    "checks": { "type": "array", "items": { "$ref": "#/$defs/checkResult" } },
+                    "timestamp": { "type": "string", "format": "date-time" },
+                    "durationMs": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "description": "Wall-clock time of the whole scan; less than the sum of the checks' times, as they run in parallel."
+                    },
+                    "osInfo": { "type": "string" },
+                    "overall": { "$ref": "#/$defs/status" },
+                    "score": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "maximum": 100,
+                        "description": "Absent when no check that counts towards the score ran."
+                    },
+                    "locale": { "$ref": "#/$defs/locale" },
+                    "redacted": {
+                        "type": "boolean",
+                        "description": "Personal details were replaced by placeholders. Absent means false."
+                    },
+                    "inventory": {
+                        "$ref": "#/$defs/inventory",
+                        "description": "Notebooks OneNote had open. Absent if the scan did not read them."
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// How `values` appear in the serialized report.
+fn names<T: Serialize>(values: &[T]) -> Vec<Value> {
+    values
+        .iter()
+        .map(|v| serde_json::to_value(v).expect("enum serializes to a string"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CheckResult;
+
+    #[test]
+    fn the_committed_schema_is_up_to_date() {
+        let committed: Value =
+            serde_json::from_str(include_str!("../../../schemas/readiness-report.v1.schema.json")).unwrap();
+        assert!(
+            committed == schema(),
+            "schemas/readiness-report.v1.schema.json differs from `schema`; regenerate it with `onenote-to-joplin schema`"
+        );
+    }
+
+    #[test]
+    fn reports_round_trip_with_the_schema_version() {
+        let scan = ScanResult {
+            checks: vec![CheckResult {
+                id: "onenote".into(),
+                label: "OneNote Desktop".into(),
+                status: CheckStatus::Warning,
+                message: "OneNote 2016 found".into(),
+                ..Default::default()
+            }],
+            timestamp: "2024-05-02T08:14:31+00:00".into(),
+            duration_ms: 1905,
+            os_info: "Windows 11 (build 22631)".into(),
+            overall: CheckStatus::Warning,
+            score: Some(80),
+            locale: Locale::En,
+            redacted: false,
+            inventory: None,
+        };
+        let json = serde_json::to_value(JsonReport::new(&scan)).unwrap();
+        assert_eq!(json["schemaVersion"], SCHEMA_VERSION);
+        assert_eq!(json["$schema"], SCHEMA_ID);
+        assert_eq!(json["scan"]["checks"][0]["status"], "warning");
+
+        let report: JsonReport = serde_json::from_value(json).unwrap();
+        assert_eq!(report.schema_version, SCHEMA_VERSION);
+        assert_eq!(report.scan.score, Some(80));
+        assert_eq!(report.scan.checks[0].message, "OneNote 2016 found");
+        let ids: Vec<_> = report.catalog.iter().map(|entry| entry.id.as_str()).collect();
+        let builtin: Vec<_> = CheckRegistry::builtin().iter().map(|c| c.id().to_string()).collect();
+        assert_eq!(ids, builtin);
+    }
+}
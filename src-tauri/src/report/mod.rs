@@ -0,0 +1,117 @@
+//! Renders a [`ScanResult`] in the formats `generate_report` and the CLI offer:
+//! JSON and SARIF through serde, the others through [`template`].
+
+pub mod fleet;
+pub mod json;
+pub mod redact;
+pub mod sarif;
+pub mod template;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Locale;
+use crate::types::{CheckStatus, RemediationStep, ScanResult, Severity};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    /// A [`json::JsonReport`], described by [`json::schema`].
+    Json,
+    /// A single page that opens in any browser, with everything inline.
+    Html,
+    /// JUnit XML for test-result aware pipelines, one testcase per check.
+    Junit,
+    /// SARIF 2.1.0 for security dashboards.
+    Sarif,
+}
+
+impl ReportFormat {
+    pub const ALL: [ReportFormat; 5] = [
+        ReportFormat::Markdown,
+        ReportFormat::Json,
+        ReportFormat::Html,
+        ReportFormat::Junit,
+        ReportFormat::Sarif,
+    ];
+
+    /// Name used by the CLI and the frontend, e.g. "json".
+    pub fn name(self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "markdown",
+            ReportFormat::Json => "json",
+            ReportFormat::Html => "html",
+            ReportFormat::Junit => "junit",
+            ReportFormat::Sarif => "sarif",
+        }
+    }
+
+    /// Accepts [`Self::name`] or the file extension, e.g. "md".
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|f| f.name() == name || f.extension() == name)
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Json => "json",
+            ReportFormat::Html => "html",
+            ReportFormat::Junit => "xml",
+            ReportFormat::Sarif => "sarif",
+        }
+    }
+}
+
+pub fn render(results: &ScanResult, format: ReportFormat) -> Result<String, String> {
+    match format {
+        ReportFormat::Markdown => template::render_builtin("report.md", results),
+        ReportFormat::Html => template::render_builtin("report.html", results),
+        ReportFormat::Junit => template::render_builtin("junit.xml", results),
+        ReportFormat::Json => serde_json::to_string_pretty(&json::JsonReport::new(results))
+            .map_err(|e| format!("Failed to serialize report: {}", e)),
+        ReportFormat::Sarif => serde_json::to_string_pretty(&sarif::log(results))
+            .map_err(|e| format!("Failed to serialize report: {}", e)),
+    }
+}
+
+fn overall_key(status: &CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Pass => "report.overall.pass",
+        CheckStatus::Warning => "report.overall.warning",
+        CheckStatus::Fail => "report.overall.fail",
+        CheckStatus::Skipped => "report.overall.skipped",
+        CheckStatus::Timeout => "report.overall.timeout",
+    }
+}
+
+fn status_key(status: &CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Pass => "status.pass",
+        CheckStatus::Warning => "status.warning",
+        CheckStatus::Fail => "status.fail",
+        CheckStatus::Skipped => "status.skipped",
+        CheckStatus::Timeout => "status.timeout",
+    }
+}
+
+fn severity_key(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Blocker => "severity.blocker",
+        Severity::Major => "severity.major",
+        Severity::Minor => "severity.minor",
+        Severity::Info => "severity.info",
+    }
+}
+
+/// Remediation as numbered plain-text lines, for formats without markup.
+fn numbered_steps(locale: Locale, steps: &[RemediationStep]) -> String {
+    steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| format!("{}. {}\n", i + 1, locale.step_text(step)))
+        .collect()
+}
@@ -0,0 +1,281 @@
+//! Strips personal details from a scan before its report is shared, e.g. on
+//! a public forum. The scan itself, and the history, keep the real values.
+
+use crate::checks::hierarchy::{Section, SectionGroup};
+use crate::types::{RemediationStep, ScanResult};
+
+pub const PROFILE_PLACEHOLDER: &str = "%USERPROFILE%";
+pub const USER_PLACEHOLDER: &str = "<user>";
+pub const MACHINE_PLACEHOLDER: &str = "<machine>";
+
+/// Profile folders that belong to no particular person.
+const SHARED_PROFILES: [&str; 4] = ["Public", "Default", "Default User", "All Users"];
+
+/// Replaces the identities of one machine with placeholders.
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    profile_dir: Option<String>,
+    user: Option<String>,
+    machine: Option<String>,
+}
+
+impl Redactor {
+    pub fn new(profile_dir: Option<String>, user: Option<String>, machine: Option<String>) -> Self {
+        let non_empty = |s: Option<String>| s.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        Self {
+            profile_dir: non_empty(profile_dir).map(|d| d.trim_end_matches(['\\', '/']).to_string()),
+            user: non_empty(user),
+            machine: non_empty(machine),
+        }
+    }
+
+    /// The current user's profile folder, user name and computer name.
+    pub fn for_this_machine() -> Self {
+        let var = |names: &[&str]| names.iter().find_map(|n| std::env::var(n).ok());
+        Self::new(
+            var(&["USERPROFILE", "HOME"]),
+            var(&["USERNAME", "USER"]),
+            var(&["COMPUTERNAME", "HOSTNAME"]),
+        )
+    }
+
+    /// A copy of `scan` with every message, label, evidence item, remediation
+    /// step and notebook inventory entry redacted, marked as such.
+    pub fn apply(&self, scan: &ScanResult) -> ScanResult {
+        let mut scan = scan.clone();
+        self.redact(&mut scan.os_info);
+        for check in &mut scan.checks {
+            self.redact(&mut check.label);
+            self.redact(&mut check.message);
+            for item in &mut check.evidence {
+                self.redact(&mut item.key);
+                self.redact(&mut item.value);
+                self.redact(&mut item.source);
+            }
+            for step in &mut check.remediation {
+                match step {
+                    RemediationStep::Instruction { text } => self.redact(text),
+                    RemediationStep::MenuPath { path } => path.iter_mut().for_each(|p| self.redact(p)),
+                    RemediationStep::Link { label, url } => {
+                        self.redact(label);
+                        self.redact(url);
+                    }
+                    RemediationStep::Command { command, description } => {
+                        self.redact(command);
+                        self.redact(description);
+                    }
+                    RemediationStep::AutoFix { description, .. } => self.redact(description),
+                }
+            }
+        }
+        if let Some(inventory) = &mut scan.inventory {
+            for notebook in &mut inventory.notebooks {
+                self.redact(&mut notebook.name);
+                self.redact(&mut notebook.path);
+                self.redact_contents(&mut notebook.section_groups, &mut notebook.sections);
+            }
+        }
+        scan.redacted = true;
+        scan
+    }
+
+    /// Names and paths inside a notebook or section group.
+    fn redact_contents(&self, groups: &mut [SectionGroup], sections: &mut [Section]) {
+        for group in groups {
+            self.redact(&mut group.name);
+            self.redact(&mut group.path);
+            self.redact_contents(&mut group.section_groups, &mut group.sections);
+        }
+        for section in sections {
+            self.redact(&mut section.name);
+            self.redact(&mut section.path);
+            for page in &mut section.pages {
+                self.redact(&mut page.name);
+            }
+        }
+    }
+
+    pub fn redact(&self, text: &mut String) {
+        if let Some(dir) = &self.profile_dir {
+            *text = replace_ignore_case(text, dir, PROFILE_PLACEHOLDER, false);
+        }
+        // Other people's profiles, e.g. in a path read from HKLM.
+        *text = redact_profile_paths(text);
+        if let Some(machine) = &self.machine {
+            *text = replace_ignore_case(text, machine, MACHINE_PLACEHOLDER, true);
+        }
+        if let Some(user) = &self.user {
+            *text = replace_ignore_case(text, user, USER_PLACEHOLDER, true);
+        }
+    }
+}
+
+/// Replace `from` regardless of ASCII case. With `whole_word`, only where it
+/// is not part of a longer word, so a user "ann" leaves "Announcements" alone,
+/// nor of a placeholder already inserted.
+fn replace_ignore_case(text: &str, from: &str, to: &str, whole_word: bool) -> String {
+    let bytes = text.as_bytes();
+    let needle = from.as_bytes();
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || b >= 0x80 || matches!(b, b'<' | b'>' | b'%');
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut i = 0;
+    while i + needle.len() <= bytes.len() {
+        let found = bytes[i..i + needle.len()].eq_ignore_ascii_case(needle)
+            && (!whole_word
+                || ((i == 0 || !is_word(bytes[i - 1]))
+                    && bytes.get(i + needle.len()).is_none_or(|&b| !is_word(b))));
+        if found {
+            out.push_str(&text[last..i]);
+            out.push_str(to);
+            i += needle.len();
+            last = i;
+        } else {
+            i += 1;
+        }
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+/// Replace the folder name after `\Users\` or `/Users/` in any path. The
+/// name runs to the next separator, closing quote or bracket, so names with
+/// spaces are replaced whole; the current user's is caught by the profile
+/// folder already.
+fn redact_profile_paths(text: &str) -> String {
+    const MARKER: &str = "users";
+    let is_separator = |b: u8| matches!(b, b'\\' | b'/');
+    let lower = text.to_ascii_lowercase();
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut from = 0;
+    while let Some(pos) = lower[from..].find(MARKER) {
+        let at = from + pos;
+        let start = at + MARKER.len() + 1;
+        from = at + MARKER.len();
+        if at == 0 || !is_separator(bytes[at - 1]) || !bytes.get(start - 1).is_some_and(|&b| is_separator(b)) {
+            continue;
+        }
+        // `;` separates the entries of PATH-like values.
+        let end = text[start..]
+            .find(['\\', '/', '"', ')', ']', ';', '\r', '\n'])
+            .map_or(text.len(), |n| start + n);
+        let name = &text[start..end];
+        if !name.is_empty()
+            && name != USER_PLACEHOLDER
+            && !SHARED_PROFILES.iter().any(|s| s.eq_ignore_ascii_case(name))
+        {
+            out.push_str(&text[last..start]);
+            out.push_str(USER_PLACEHOLDER);
+            last = end;
+        }
+        from = end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Locale;
+    use crate::types::{CheckResult, CheckStatus, Evidence};
+
+    fn redactor() -> Redactor {
+        Redactor::new(
+            Some("C:\\Users\\jdoe\\".into()),
+            Some("jdoe".into()),
+            Some("DESKTOP-7QK2".into()),
+        )
+    }
+
+    fn scan(message: &str, evidence: Evidence) -> ScanResult {
+        ScanResult {
+            checks: vec![CheckResult {
+                id: "onenote".into(),
+                label: "OneNote".into(),
+                status: CheckStatus::Pass,
+                message: message.into(),
+                remediation: vec![RemediationStep::Command {
+                    command: format!("explorer {}", evidence.source),
+                    description: "Open the folder".into(),
+                }],
+                evidence: vec![evidence],
+                ..Default::default()
+            }],
+            timestamp: "2024-05-02T08:14:31+00:00".into(),
+            duration_ms: 0,
+            os_info: "Windows 11 on desktop-7qk2".into(),
+            overall: CheckStatus::Pass,
+            score: Some(100),
+            locale: Locale::En,
+            redacted: false,
+            inventory: None,
+        }
+    }
+
+    fn redacted(text: &str) -> String {
+        let mut text = text.to_string();
+        redactor().redact(&mut text);
+        text
+    }
+
+    #[test]
+    fn replaces_the_profile_user_and_machine_in_any_case() {
+        let scan = scan(
+            "Signed in as JDoe on Desktop-7QK2",
+            Evidence::new("Path", "c:\\users\\JDOE\\Documents\\OneNote Notebooks", "HKCU"),
+        );
+        let redacted = redactor().apply(&scan);
+        assert!(redacted.redacted);
+        assert_eq!(redacted.os_info, "Windows 11 on <machine>");
+        let check = &redacted.checks[0];
+        assert_eq!(check.message, "Signed in as <user> on <machine>");
+        assert_eq!(check.evidence[0].value, "%USERPROFILE%\\Documents\\OneNote Notebooks");
+        assert_eq!(
+            check.remediation[0],
+            RemediationStep::Command {
+                command: "explorer HKCU".into(),
+                description: "Open the folder".into()
+            }
+        );
+        // The scan handed in is left as it was.
+        assert_eq!(scan.checks[0].message, "Signed in as JDoe on Desktop-7QK2");
+    }
+
+    #[test]
+    fn user_names_inside_words_are_kept() {
+        assert_eq!(redacted("jdoes and xjdoe stay, jdoe goes"), "jdoes and xjdoe stay, <user> goes");
+    }
+
+    #[test]
+    fn other_profiles_are_replaced_with_either_separator() {
+        assert_eq!(
+            redacted("C:\\Users\\asmith\\AppData and C:/Users/asmith/AppData"),
+            "C:\\Users\\<user>\\AppData and C:/Users/<user>/AppData"
+        );
+        assert_eq!(redacted("/Users/asmith/Library"), "/Users/<user>/Library");
+        assert_eq!(redacted("\"C:\\Users\\asmith\""), "\"C:\\Users\\<user>\"");
+    }
+
+    #[test]
+    fn names_with_spaces_are_replaced_whole() {
+        assert_eq!(
+            redacted("Notebooks in C:\\Users\\John Smith\\Notes (C:\\Users\\Jane Roe)"),
+            "Notebooks in C:\\Users\\<user>\\Notes (C:\\Users\\<user>)"
+        );
+    }
+
+    #[test]
+    fn shared_profiles_are_kept() {
+        let text = "C:\\Users\\Public\\Documents; C:\\Users\\Default\\NTUSER.DAT; C:/Users/All Users/x";
+        assert_eq!(redacted(text), text);
+    }
+
+    #[test]
+    fn words_that_only_contain_users_are_kept() {
+        let text = "Power users\\admins and C:\\Program Files\\superusers\\bin";
+        assert_eq!(redacted(text), text);
+    }
+}
@@ -0,0 +1,111 @@
+//! SARIF 2.1.0 log for security dashboards. Each check is a rule and each
+//! check result a result; passes are kept as `kind: "pass"` so a dashboard
+//! can tell a resolved issue from a check that did not run.
+
+use serde_json::{json, Value};
+
+use crate::types::{CheckStatus, ScanResult, Severity};
+
+use super::numbered_steps;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/dimaginar/onenote-to-joplin";
+
+pub fn log(results: &ScanResult) -> Value {
+    let locale = results.locale;
+    let rules: Vec<Value> = results
+        .checks
+        .iter()
+        .map(|check| {
+            let mut rule = json!({
+                "id": check.id,
+                "shortDescription": { "text": check.label },
+                "defaultConfiguration": { "level": severity_level(check.severity) },
+                "properties": {
+                    "severity": check.severity,
+                    "weight": check.weight,
+                },
+            });
+            if !check.remediation.is_empty() {
+                rule["help"] = json!({ "text": numbered_steps(locale, &check.remediation) });
+            }
+            rule
+        })
+        .collect();
+
+    let findings: Vec<Value> = results
+        .checks
+        .iter()
+        .enumerate()
+        .map(|(index, check)| {
+            let (kind, level) = match check.status {
+                CheckStatus::Fail | CheckStatus::Timeout => ("fail", severity_level(check.severity)),
+                CheckStatus::Warning => ("fail", "warning"),
+                CheckStatus::Pass => ("pass", "none"),
+                CheckStatus::Skipped => ("notApplicable", "none"),
+            };
+            json!({
+                "ruleId": check.id,
+                "ruleIndex": index,
+                "kind": kind,
+                "level": level,
+                "message": { "text": check.message },
+                "properties": {
+                    "status": check.status,
+                    "evidence": check.evidence,
+                    "remediation": check.remediation,
+                },
+            })
+        })
+        .collect();
+
+    let mut properties = json!({
+        "osInfo": results.os_info,
+        "overall": results.overall,
+        "redacted": results.redacted,
+    });
+    if let Some(score) = results.score {
+        properties["score"] = json!(score);
+    }
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": INFORMATION_URI,
+                    "rules": rules,
+                },
+            },
+            "invocations": [{
+                "executionSuccessful": true,
+                "endTimeUtc": utc(&results.timestamp),
+            }],
+            "language": locale.code(),
+            "results": findings,
+            "properties": properties,
+        }],
+    })
+}
+
+/// SARIF wants UTC times with a `Z` suffix; scans store an RFC 3339 offset.
+fn utc(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| {
+            t.with_timezone(&chrono::Utc)
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+        })
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+/// SARIF level of a failed check: blockers and major issues are errors.
+fn severity_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Blocker | Severity::Major => "error",
+        Severity::Minor => "warning",
+        Severity::Info => "note",
+    }
+}
@@ -0,0 +1,382 @@
+//! Text reports rendered from minijinja templates. Values are escaped for
+//! the output format, picked from the template's file extension, so a `|` in
+//! a message cannot break a Markdown table nor a `<` the HTML.
+//!
+//! The built-in templates can be replaced per site: a file with the same name
+//! in a `templates` folder next to the policy file is used instead.
+
+use std::path::Path;
+
+use minijinja::{escape_formatter, AutoEscape, Environment, State, Value};
+use serde::Serialize;
+
+use crate::checks::policy;
+use crate::i18n::Locale;
+use crate::types::{CheckResult, CheckStatus, RemediationStep, ScanResult};
+
+use super::{numbered_steps, overall_key, severity_key, status_key};
+
+/// Folder, next to the policy file, that overrides the built-in templates.
+pub const TEMPLATE_DIR: &str = "templates";
+
+const BUILTIN: [(&str, &str); 5] = [
+    ("report.md", include_str!("../../templates/report.md")),
+    ("report.html", include_str!("../../templates/report.html")),
+    ("junit.xml", include_str!("../../templates/junit.xml")),
+    ("fleet.md", include_str!("../../templates/fleet.md")),
+    ("fleet.html", include_str!("../../templates/fleet.html")),
+];
+
+/// Render the built-in template `name`, or its site override if there is one.
+pub fn render_builtin(name: &str, results: &ScanResult) -> Result<String, String> {
+    render_context(name, results.locale, ReportContext::new(results))
+}
+
+/// Like [`render_builtin`], for templates that are not about a single scan.
+pub fn render_context<S: Serialize>(name: &str, locale: Locale, context: S) -> Result<String, String> {
+    let custom = policy::config_dirs()
+        .into_iter()
+        .map(|dir| dir.join(TEMPLATE_DIR).join(name))
+        .find(|path| path.is_file());
+    let source = match custom {
+        Some(path) => std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read template {}: {}", path.display(), e))?,
+        None => BUILTIN
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, source)| source.to_string())
+            .ok_or_else(|| format!("Unknown template '{}'", name))?,
+    };
+    render(name, &source, locale, context)
+}
+
+/// Render a user-supplied template. Its extension (`.md`, `.html`, `.xml`)
+/// decides how values are escaped; other files get them unescaped.
+pub fn render_file(path: &Path, results: &ScanResult) -> Result<String, String> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read template {}: {}", path.display(), e))?;
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    render(&name, &source, results.locale, ReportContext::new(results))
+}
+
+fn render<S: Serialize>(name: &str, source: &str, locale: Locale, context: S) -> Result<String, String> {
+    let env = environment(locale);
+    let template = env
+        .template_from_named_str(name, source)
+        .map_err(|e| format!("Invalid template {}: {}", name, e))?;
+    template
+        .render(context)
+        .map_err(|e| format!("Failed to render template {}: {}", name, e))
+}
+
+fn environment(locale: Locale) -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.set_keep_trailing_newline(true);
+    env.set_auto_escape_callback(|name| match name.rsplit('.').next() {
+        Some("html" | "htm") => AutoEscape::Html,
+        Some("xml") => AutoEscape::Custom("xml"),
+        Some("md") => AutoEscape::Custom("markdown"),
+        _ => AutoEscape::None,
+    });
+    env.set_formatter(|out, state, value| match state.auto_escape() {
+        AutoEscape::Custom(format) => {
+            if value.is_undefined() || value.is_none() {
+                return Ok(());
+            }
+            let text = value.to_string();
+            if value.is_safe() {
+                out.write_str(&text)?;
+            } else if format == "xml" {
+                out.write_str(&escape_xml(&text))?;
+            } else {
+                out.write_str(&escape_markdown(&text))?;
+            }
+            Ok(())
+        }
+        _ => escape_formatter(out, state, value),
+    });
+    env.add_function("t", move |key: &str| locale.t(key, &[]));
+    env.add_filter("code", code);
+    env.add_filter("seconds", |ms: u64| format!("{:.3}", ms as f64 / 1000.0));
+    env
+}
+
+/// Inline code: a code span in Markdown, `<code>` in HTML.
+fn code(state: &State, text: String) -> Value {
+    match state.auto_escape() {
+        AutoEscape::Custom("markdown") => {
+            let text = text.replace(['\r', '\n'], " ");
+            // A fence longer than any backtick run inside, as CommonMark asks.
+            let mut fence = String::from("`");
+            while text.contains(fence.as_str()) {
+                fence.push('`');
+            }
+            let pad = if text.starts_with('`') || text.ends_with('`') { " " } else { "" };
+            Value::from_safe_string(format!("{fence}{pad}{text}{pad}{fence}"))
+        }
+        AutoEscape::Html => Value::from_safe_string(format!("<code>{}</code>", escape_xml(&text))),
+        _ => Value::from(text),
+    }
+}
+
+/// Backslash-escape characters with inline meaning, and turn line breaks
+/// into `<br>` so table rows and list items stay on one line.
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => out.push_str("<br>"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escape text for XML content and attributes, dropping the control
+/// characters XML 1.0 does not allow.
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            // Kept as references so parsers do not fold them in attributes.
+            '\n' => out.push_str("&#10;"),
+            '\r' => out.push_str("&#13;"),
+            '\t' => out.push(c),
+            c if c.is_control() => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// What templates see. `scan` is the scan as in the JSON report; the other
+/// fields are derived from it so templates need no logic of their own.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportContext<'a> {
+    scan: &'a ScanResult,
+    tool: Tool,
+    overall_text: String,
+    checks: Vec<CheckView<'a>>,
+    /// Checks that recorded evidence.
+    evidence: Vec<CheckView<'a>>,
+    /// Checks that need fixing and have remediation, blockers first.
+    issues: Vec<CheckView<'a>>,
+    /// Failed and timed-out checks.
+    failures: usize,
+    /// Warnings and skipped checks.
+    skipped: usize,
+    /// Wall-clock time of the scan, not the sum of the checks' times, as the
+    /// checks run in parallel.
+    duration_ms: u64,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckView<'a> {
+    #[serde(flatten)]
+    check: &'a CheckResult,
+    status_text: String,
+    severity_text: String,
+    steps: Vec<StepView<'a>>,
+    /// The steps as numbered plain-text lines.
+    remediation_text: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StepView<'a> {
+    #[serde(flatten)]
+    step: &'a RemediationStep,
+    /// The step as one translated sentence.
+    plain_text: String,
+}
+
+impl<'a> ReportContext<'a> {
+    fn new(scan: &'a ScanResult) -> Self {
+        let locale = scan.locale;
+        let checks: Vec<CheckView> = scan
+            .checks
+            .iter()
+            .map(|check| CheckView {
+                check,
+                status_text: locale.t(status_key(&check.status), &[]),
+                severity_text: locale.t(severity_key(check.severity), &[]),
+                steps: check
+                    .remediation
+                    .iter()
+                    .map(|step| StepView {
+                        step,
+                        plain_text: locale.step_text(step),
+                    })
+                    .collect(),
+                remediation_text: numbered_steps(locale, &check.remediation),
+            })
+            .collect();
+
+        let evidence = checks
+            .iter()
+            .filter(|c| !c.check.evidence.is_empty())
+            .cloned()
+            .collect();
+        let mut issues: Vec<CheckView> = checks
+            .iter()
+            .filter(|c| {
+                c.check.status != CheckStatus::Pass
+                    && c.check.status != CheckStatus::Skipped
+                    && !c.check.remediation.is_empty()
+            })
+            .cloned()
+            .collect();
+        issues.sort_by_key(|c| c.check.severity);
+        let count = |f: fn(&CheckStatus) -> bool| scan.checks.iter().filter(|c| f(&c.status)).count();
+
+        Self {
+            scan,
+            tool: Tool {
+                name: env!("CARGO_PKG_NAME"),
+                version: env!("CARGO_PKG_VERSION"),
+            },
+            overall_text: locale.t(overall_key(&scan.overall), &[]),
+            failures: count(|s| matches!(s, CheckStatus::Fail | CheckStatus::Timeout)),
+            skipped: count(|s| matches!(s, CheckStatus::Warning | CheckStatus::Skipped)),
+            // Results stored before the scan's time was recorded took at
+            // least as long as their slowest check.
+            duration_ms: match scan.duration_ms {
+                0 => scan.checks.iter().map(|c| c.duration_ms).max().unwrap_or(0),
+                elapsed => elapsed,
+            },
+            evidence,
+            issues,
+            checks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Locale;
+
+    #[test]
+    fn score_is_left_out_when_there_is_none() {
+        let mut unscored = scan(1905);
+        unscored.score = None;
+        let markdown = render_builtin("report.md", &unscored).unwrap();
+        assert!(!markdown.contains("Readiness score"), "{}", markdown);
+        let html = render_builtin("report.html", &unscored).unwrap();
+        assert!(!html.contains("Readiness score"));
+        assert!(!render_builtin("junit.xml", &unscored).unwrap().contains(r#"name="score""#));
+
+        let markdown = render_builtin("report.md", &scan(1905)).unwrap();
+        assert!(markdown.contains("**Readiness score:** 100/100"), "{}", markdown);
+    }
+
+    fn scan(duration_ms: u64) -> ScanResult {
+        let check = |id: &str, duration_ms| CheckResult {
+            id: id.into(),
+            label: id.into(),
+            status: CheckStatus::Pass,
+            duration_ms,
+            ..Default::default()
+        };
+        ScanResult {
+            checks: vec![check("onenote", 1840), check("word", 1210), check("joplin", 14)],
+            timestamp: "2024-05-02T08:14:31+00:00".into(),
+            duration_ms,
+            os_info: String::new(),
+            overall: CheckStatus::Pass,
+            score: Some(100),
+            locale: Locale::En,
+            redacted: false,
+            inventory: None,
+        }
+    }
+
+    const HOSTILE: &str = "a | b\r\nline `two` <script>alert(1)</script> & \"q\" \u{7}";
+
+    fn hostile() -> ScanResult {
+        let mut scan = scan(1905);
+        scan.checks[1].status = CheckStatus::Fail;
+        scan.checks[1].message = HOSTILE.into();
+        scan.checks[1].remediation = vec![RemediationStep::Instruction { text: HOSTILE.into() }];
+        scan.overall = CheckStatus::Fail;
+        scan
+    }
+
+    #[test]
+    fn markdown_table_rows_stay_on_one_line() {
+        let markdown = render_builtin("report.md", &hostile()).unwrap();
+        let row = markdown.lines().find(|line| line.starts_with("| word |")).unwrap();
+        assert!(
+            row.contains(r#"| a \| b<br>line \`two\` \<script\>alert(1)\</script\> & "q" "#),
+            "{}",
+            row
+        );
+        assert!(row.ends_with("| 1210 ms |"), "{}", row);
+        assert!(!markdown.contains('\r'));
+    }
+
+    #[test]
+    fn html_is_entity_escaped() {
+        let html = render_builtin("report.html", &hostile()).unwrap();
+        assert!(!html.contains("<script>"));
+        assert!(
+            html.contains("a | b\r\nline `two` &lt;script&gt;alert(1)&lt;&#x2f;script&gt; &amp; &quot;q&quot;"),
+            "{}",
+            html
+        );
+    }
+
+    #[test]
+    fn junit_is_well_formed_xml() {
+        let xml = render_builtin("junit.xml", &hostile()).unwrap();
+        let document = roxmltree::Document::parse(&xml).unwrap();
+        let failure = document
+            .descendants()
+            .find(|node| node.has_tag_name("failure"))
+            .unwrap();
+        // Control characters XML cannot hold are dropped; the rest survives.
+        let expected = "a | b\r\nline `two` <script>alert(1)</script> & \"q\" ";
+        assert_eq!(failure.attribute("message"), Some(expected));
+        assert!(failure.text().unwrap().contains(expected));
+        assert!(xml.contains("&lt;script&gt;alert(1)&lt;/script&gt; &amp; &quot;q&quot;"));
+    }
+
+    #[test]
+    fn junit_suite_time_is_the_scans_wall_time() {
+        let xml = render_builtin("junit.xml", &scan(1905)).unwrap();
+        assert!(xml.contains(r#"<testsuites name="#));
+        assert_eq!(xml.matches(r#"time="1.905""#).count(), 2, "{}", xml);
+        assert!(xml.contains(r#"name="onenote" time="1.840""#));
+        assert!(xml.contains(r#"name="word" time="1.210""#));
+    }
+
+    #[test]
+    fn junit_suite_time_of_older_results_is_the_slowest_check() {
+        let xml = render_builtin("junit.xml", &scan(0)).unwrap();
+        assert_eq!(xml.matches(r#"time="1.840""#).count(), 3, "{}", xml);
+    }
+}
@@ -1,22 +1,169 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+use crate::checks::hierarchy::Inventory;
+use crate::i18n::Locale;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum CheckStatus {
     Pass,
     Fail,
     Warning,
+    #[default]
     Skipped,
+    Timeout,
+}
+
+/// How much a failing check stands in the way of a migration.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The export cannot run at all.
+    Blocker,
+    /// The export runs but is likely to lose content or fail midway.
+    #[default]
+    Major,
+    /// Worth fixing, but the export will generally succeed.
+    Minor,
+    /// Informational only; does not affect the readiness score.
+    Info,
+}
+
+impl Severity {
+    /// Default weight of a check with this severity in the readiness score.
+    pub fn default_weight(self) -> u32 {
+        match self {
+            Severity::Blocker => 40,
+            Severity::Major => 20,
+            Severity::Minor => 10,
+            Severity::Info => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckCategory {
+    Joplin,
+    System,
+    Office,
+    Sync,
+    /// Rules added through the policy file.
+    Policy,
 }
 
+/// Static description of a registered check, available before a scan runs.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct CheckInfo {
+    pub id: String,
+    pub label: String,
+    pub category: CheckCategory,
+    pub depends_on: Vec<String>,
+    pub severity: Severity,
+    pub weight: u32,
+}
+
+/// One observation a check based its verdict on, e.g. the registry value
+/// `SyncStateOffline = 1` read from `HKCU\...\OneNote\Options\Save`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Evidence {
+    pub key: String,
+    pub value: String,
+    /// Where the value came from: a registry key, "File system" or "COM".
+    pub source: String,
+}
+
+impl Evidence {
+    pub fn new(key: impl Into<String>, value: impl Into<String>, source: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+            source: source.into(),
+        }
+    }
+}
+
+/// One step of the advice shown for a check that did not pass. Steps are kept
+/// structured so the UI and the report can render menu paths, links and
+/// commands appropriately.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum RemediationStep {
+    /// Free-form advice.
+    Instruction { text: String },
+    /// A path through menus or settings pages, e.g. File, Options, Sync.
+    MenuPath { path: Vec<String> },
+    Link { label: String, url: String },
+    /// A command line the user can run.
+    Command { command: String, description: String },
+    /// An automatic fix that `fix_check` can apply for `check_id`.
+    AutoFix { check_id: String, description: String },
+}
+
+impl RemediationStep {
+    pub fn instruction(text: impl Into<String>) -> Self {
+        Self::Instruction { text: text.into() }
+    }
+
+    pub fn menu(path: &[&str]) -> Self {
+        Self::MenuPath {
+            path: path.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    pub fn link(label: impl Into<String>, url: impl Into<String>) -> Self {
+        Self::Link {
+            label: label.into(),
+            url: url.into(),
+        }
+    }
+
+    pub fn command(command: impl Into<String>, description: impl Into<String>) -> Self {
+        Self::Command {
+            command: command.into(),
+            description: description.into(),
+        }
+    }
+}
+
+/// Accepts the single remediation string written by older snapshots as well
+/// as a list of steps.
+fn remediation_steps<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<RemediationStep>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Steps(Vec<RemediationStep>),
+        Text(Option<String>),
+    }
+    Ok(match Stored::deserialize(d)? {
+        Stored::Steps(steps) => steps,
+        Stored::Text(text) => text.into_iter().map(RemediationStep::instruction).collect(),
+    })
+}
+
+/// Outcome of one check. `evidence` is filled in by the check itself; the
+/// remaining defaulted fields are stamped by the scheduler.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CheckResult {
     pub id: String,
     pub label: String,
     pub status: CheckStatus,
     pub message: String,
-    pub remediation: Option<String>,
+    /// What to do about a failure or warning, in order. Empty for passes.
+    #[serde(default, deserialize_with = "remediation_steps")]
+    pub remediation: Vec<RemediationStep>,
+    #[serde(default)]
+    pub evidence: Vec<Evidence>,
+    /// Wall-clock time the check took. Zero for checks that never ran.
+    #[serde(default)]
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(default)]
+    pub weight: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,14 +171,32 @@ pub struct CheckResult {
 pub struct ScanResult {
     pub checks: Vec<CheckResult>,
     pub timestamp: String,
+    /// Wall-clock time of the whole scan. Checks run in parallel, so this is
+    /// less than the sum of their durations. Zero for older results.
+    #[serde(default)]
+    pub duration_ms: u64,
     pub os_info: String,
     pub overall: CheckStatus,
+    /// Weighted readiness from 0 (nothing in place) to 100 (ready to migrate).
+    /// `None` when no check that counts towards it ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<u8>,
+    /// Language the messages were produced in; the report uses it too.
+    #[serde(default)]
+    pub locale: Locale,
+    /// Personal details were replaced by placeholders for sharing.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
+    /// Notebooks OneNote reported, if the scan could read them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inventory: Option<Inventory>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ScanError {
     RegistryAccessDenied(String),
     ComInitFailed(String),
+    Cancelled,
     Unexpected(String),
 }
 
@@ -40,6 +205,7 @@ impl std::fmt::Display for ScanError {
         match self {
             ScanError::RegistryAccessDenied(msg) => write!(f, "Registry access denied: {}", msg),
             ScanError::ComInitFailed(msg) => write!(f, "COM initialization failed: {}", msg),
+            ScanError::Cancelled => write!(f, "Scan cancelled"),
             ScanError::Unexpected(msg) => write!(f, "Unexpected error: {}", msg),
         }
     }
@@ -1,3 +1,4 @@
+import { useEffect } from "react";
 import { useReadinessScan } from "./hooks/useReadinessScan";
 import { useKeyboard } from "./hooks/useKeyboard";
 import { EmptyState } from "./components/scan/EmptyState";
@@ -5,12 +6,18 @@ import { ScanningState } from "./components/scan/ScanningState";
 import { ResultsView } from "./components/results/ResultsView";
 import { WizardView } from "./components/wizard/WizardView";
 import { StatusBar } from "./components/layout/StatusBar";
+import { useAppStore } from "./stores/appStore";
 import { cn } from "./utils/cn";
 
 function App() {
   const { view } = useReadinessScan();
+  const loadLocale = useAppStore((s) => s.loadLocale);
   useKeyboard();
 
+  useEffect(() => {
+    loadLocale();
+  }, [loadLocale]);
+
   const isList = view === "results" || view === "wizard";
 
   return (
@@ -1,4 +1,6 @@
 import { useStatusBar } from "../../hooks/useStatusBar";
+import { useT } from "../../hooks/useT";
+import { LOCALES, isLocale } from "../../i18n";
 import { cn } from "../../utils/cn";
 
 const statusColors = {
@@ -8,14 +10,30 @@ const statusColors = {
 };
 
 export function StatusBar() {
-  const { statusMessage, statusType } = useStatusBar();
+  const { statusMessage, statusType, locale, setLocale } = useStatusBar();
+  const t = useT();
 
   return (
     <div className="flex items-center justify-between border-t border-border bg-card/50 px-4 py-1.5">
       <span className={cn("text-xs", statusColors[statusType])}>
         {statusMessage}
       </span>
-      <span className="text-xs text-muted-foreground">v0.2.0</span>
+      <div className="flex items-center gap-3">
+        <select
+          aria-label={t("app.language")}
+          title={t("app.language")}
+          value={locale}
+          onChange={(e) => isLocale(e.target.value) && setLocale(e.target.value)}
+          className="bg-transparent text-xs text-muted-foreground outline-none cursor-pointer"
+        >
+          {LOCALES.map((l) => (
+            <option key={l.code} value={l.code}>
+              {l.name}
+            </option>
+          ))}
+        </select>
+        <span className="text-xs text-muted-foreground">v0.2.0</span>
+      </div>
     </div>
   );
 }
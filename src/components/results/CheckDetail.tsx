@@ -4,39 +4,49 @@ import {
   AlertTriangle,
   CircleHelp,
   MinusCircle,
+  TimerOff,
   Wrench,
-  ExternalLink,
+  Undo2,
 } from "lucide-react";
-import { openUrl } from "@tauri-apps/plugin-opener";
-import { isTauri } from "../../utils/tauri";
-import type { CheckResult } from "../../stores/types";
+import { useState } from "react";
+import { useAppStore } from "../../stores/appStore";
+import { useT } from "../../hooks/useT";
+import type {
+  CheckResult,
+  Evidence,
+  FixChange,
+  RegValue,
+  RemediationStep,
+} from "../../stores/types";
 import { cn } from "../../utils/cn";
 import { Button } from "../ui/button";
+import { RemediationSteps } from "./RemediationSteps";
 
 const statusConfig = {
   pass: {
     icon: CircleCheck,
     color: "text-success",
     bg: "bg-success/10",
-    label: "Passed",
   },
   fail: {
     icon: CircleX,
     color: "text-destructive-foreground",
     bg: "bg-destructive/10",
-    label: "Failed",
   },
   warning: {
     icon: AlertTriangle,
     color: "text-warning",
     bg: "bg-warning/10",
-    label: "Warning",
   },
   skipped: {
     icon: MinusCircle,
     color: "text-muted-foreground",
     bg: "bg-muted/10",
-    label: "Skipped",
+  },
+  timeout: {
+    icon: TimerOff,
+    color: "text-destructive-foreground",
+    bg: "bg-destructive/10",
   },
 };
 
@@ -51,17 +61,20 @@ export function CheckDetail({
   issueCount,
   onStartGuide,
 }: CheckDetailProps) {
+  const fixesApplied = useAppStore((s) => s.fixesApplied);
+  const t = useT();
+
   if (!check) {
     return (
       <div className="flex flex-col items-center justify-center h-full px-8 text-center">
         <CircleHelp className="h-12 w-12 mb-4 text-muted-foreground/40" />
         <h3 className="text-base font-medium mb-1">
-          Select a check to review
+          {t("detail.selectCheck")}
         </h3>
         <p className="text-sm text-muted-foreground max-w-xs">
           {issueCount > 0
-            ? `${issueCount} item${issueCount !== 1 ? "s" : ""} need attention before migration`
-            : "All checks passed \u2014 you're ready to migrate"}
+            ? t("detail.needAttention", { count: issueCount })
+            : t("detail.allPassed")}
         </p>
       </div>
     );
@@ -69,7 +82,12 @@ export function CheckDetail({
 
   const config = statusConfig[check.status];
   const Icon = config.icon;
-  const hasRemediation = check.remediation && check.status !== "pass";
+  // Automatic fixes get their own panel with preview and apply buttons.
+  const steps = check.remediation.filter((s) => s.kind !== "autoFix");
+  const autoFix = check.remediation.find(
+    (s): s is Extract<RemediationStep, { kind: "autoFix" }> => s.kind === "autoFix"
+  );
+  const hasRemediation = steps.length > 0 && check.status !== "pass";
 
   return (
     <div className="p-5 space-y-4">
@@ -80,7 +98,9 @@ export function CheckDetail({
         <div>
           <h3 className="text-base font-semibold">{check.label}</h3>
           <p className="text-sm text-muted-foreground mt-0.5">
-            {config.label}
+            {t(`checkStatus.${check.status}`)}
+            {` \u00b7 ${t(`severity.${check.severity}`)}`}
+            {check.durationMs > 0 && ` \u00b7 ${check.durationMs} ms`}
           </p>
         </div>
       </div>
@@ -91,62 +111,134 @@ export function CheckDetail({
 
       {hasRemediation && (
         <div className="space-y-3">
-          <h4 className="text-sm font-medium">How to fix</h4>
+          <h4 className="text-sm font-medium">{t("detail.howToFix")}</h4>
           <div className="rounded-lg border border-warning/20 bg-warning/5 p-4">
-            <p className="text-sm leading-relaxed">
-              <TextWithLinks text={check.remediation!} />
-            </p>
+            <RemediationSteps key={check.id} steps={steps} />
           </div>
           {onStartGuide && (
             <Button size="sm" onClick={onStartGuide} className="gap-1.5">
               <Wrench className="h-3.5 w-3.5" />
-              Start Guided Fix
+              {t("detail.startGuide")}
             </Button>
           )}
         </div>
       )}
 
+      {(autoFix || fixesApplied) && (
+        <FixPanel
+          key={check.id}
+          checkId={check.id}
+          description={autoFix?.description ?? null}
+        />
+      )}
+
       {check.status === "pass" && (
-        <p className="text-sm text-muted-foreground">
-          No action required. This check passed successfully.
-        </p>
+        <p className="text-sm text-muted-foreground">{t("detail.passed")}</p>
       )}
 
       {check.status === "skipped" && (
-        <p className="text-sm text-muted-foreground">
-          This check was skipped because OneNote Desktop is not installed, so it does not apply. No action is required.
-        </p>
+        <p className="text-sm text-muted-foreground">{t("detail.skipped")}</p>
       )}
+
+      {check.evidence.length > 0 && <EvidenceList evidence={check.evidence} />}
     </div>
   );
 }
 
-function TextWithLinks({ text }: { text: string }) {
-  const parts = text.split(/(https?:\/\/\S+)/g);
+/**
+ * Opt-in registry fix: preview the change first, then apply it. Applied fixes
+ * can be undone together.
+ */
+function FixPanel({
+  checkId,
+  description,
+}: {
+  checkId: string;
+  description: string | null;
+}) {
+  const fixCheck = useAppStore((s) => s.fixCheck);
+  const revertFixes = useAppStore((s) => s.revertFixes);
+  const fixesApplied = useAppStore((s) => s.fixesApplied);
+  const [preview, setPreview] = useState<FixChange | null>(null);
+  const [busy, setBusy] = useState(false);
+  const t = useT();
+
+  const run = async (action: () => Promise<void>) => {
+    setBusy(true);
+    try {
+      await action();
+    } finally {
+      setBusy(false);
+    }
+  };
+  const showPreview = () =>
+    run(async () => setPreview((await fixCheck(checkId, true))?.change ?? null));
+  const apply = () =>
+    run(async () => {
+      await fixCheck(checkId, false);
+      setPreview(null);
+    });
+
   return (
-    <>
-      {parts.map((part, i) =>
-        part.match(/^https?:\/\//) ? (
-          <a
-            key={i}
-            href={part}
-            onClick={(e) => {
-              e.preventDefault();
-              if (isTauri()) {
-                openUrl(part);
-              } else {
-                window.open(part, "_blank");
-              }
-            }}
-            className="inline-flex items-center gap-0.5 text-primary hover:underline"
-          >
-            {part}
-            <ExternalLink className="h-3 w-3 inline shrink-0" />
-          </a>
-        ) : (
-          <span key={i}>{part}</span>
-        )
+    <div className="space-y-3">
+      {description && (
+        <>
+          <h4 className="text-sm font-medium">{t("fix.title")}</h4>
+          <div className="rounded-lg border border-border p-4 space-y-2">
+            <p className="text-sm leading-relaxed">{description}</p>
+            {preview && (
+              <p className="text-xs text-muted-foreground break-all">
+                <span className="font-mono">
+                  {`${preview.fix.hive}\\${preview.fix.path}!${preview.fix.name}`}
+                </span>
+                {`: ${preview.previous ? formatValue(preview.previous) : t("fix.notSet")} \u2192 ${formatValue(preview.fix.value)}`}
+              </p>
+            )}
+          </div>
+          {preview ? (
+            <Button size="sm" onClick={apply} disabled={busy || preview.unchanged} className="gap-1.5">
+              <Wrench className="h-3.5 w-3.5" />
+              {t("fix.apply")}
+            </Button>
+          ) : (
+            <Button size="sm" variant="outline" onClick={showPreview} disabled={busy} className="gap-1.5">
+              <Wrench className="h-3.5 w-3.5" />
+              {t("fix.preview")}
+            </Button>
+          )}
+        </>
       )}
-    </>
+      {fixesApplied && (
+        <Button size="sm" variant="ghost" onClick={() => run(revertFixes)} disabled={busy} className="gap-1.5">
+          <Undo2 className="h-3.5 w-3.5" />
+          {t("fix.undo")}
+        </Button>
+      )}
+    </div>
+  );
+}
+
+function formatValue(value: RegValue): string {
+  return Array.isArray(value.data) ? value.data.join("; ") : String(value.data);
+}
+
+function EvidenceList({ evidence }: { evidence: Evidence[] }) {
+  const t = useT();
+  return (
+    <div className="space-y-2">
+      <h4 className="text-sm font-medium">{t("detail.evidence")}</h4>
+      <ul className="space-y-2 rounded-lg border border-border p-3">
+        {evidence.map((item, i) => (
+          <li key={i} className="text-xs">
+            <div>
+              <span className="font-mono">{item.key}</span>
+              {" = "}
+              <span className="font-mono break-all">{item.value}</span>
+            </div>
+            <div className="text-muted-foreground break-all">{item.source}</div>
+          </li>
+        ))}
+      </ul>
+    </div>
   );
 }
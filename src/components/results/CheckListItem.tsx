@@ -1,4 +1,4 @@
-import { CircleCheck, CircleX, AlertTriangle, MinusCircle } from "lucide-react";
+import { CircleCheck, CircleX, AlertTriangle, MinusCircle, TimerOff } from "lucide-react";
 import type { CheckResult } from "../../stores/types";
 import { cn } from "../../utils/cn";
 
@@ -7,6 +7,7 @@ const statusIcon = {
   fail: CircleX,
   warning: AlertTriangle,
   skipped: MinusCircle,
+  timeout: TimerOff,
 };
 
 const statusColor = {
@@ -14,6 +15,7 @@ const statusColor = {
   fail: "text-destructive-foreground",
   warning: "text-warning",
   skipped: "text-muted-foreground",
+  timeout: "text-destructive-foreground",
 };
 
 interface CheckListItemProps {
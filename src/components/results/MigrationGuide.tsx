@@ -4,6 +4,7 @@ import {
   AlertTriangle,
 } from "lucide-react";
 import { openUrl } from "@tauri-apps/plugin-opener";
+import { useT } from "../../hooks/useT";
 import { cn } from "../../utils/cn";
 
 interface MigrationGuideProps {
@@ -12,6 +13,8 @@ interface MigrationGuideProps {
 }
 
 export function MigrationGuide({ allPassed, issueCount }: MigrationGuideProps) {
+  const t = useT();
+
   return (
     <div className="p-4 space-y-3">
       {/* Header */}
@@ -31,12 +34,12 @@ export function MigrationGuide({ allPassed, issueCount }: MigrationGuideProps) {
         </div>
         <div>
           <h3 className="text-base font-semibold">
-            {allPassed ? "Ready to Migrate" : "Migration Guide"}
+            {allPassed ? t("guide.readyTitle") : t("guide.title")}
           </h3>
           <p className="text-sm text-muted-foreground mt-0.5">
             {allPassed
-              ? "All checks passed \u2014 follow these steps to migrate your notebooks"
-              : `${issueCount} issue${issueCount !== 1 ? "s" : ""} remaining \u2014 you can preview the steps ahead`}
+              ? t("guide.allPassed")
+              : t("guide.issuesRemaining", { count: issueCount })}
           </p>
         </div>
       </div>
@@ -45,22 +48,19 @@ export function MigrationGuide({ allPassed, issueCount }: MigrationGuideProps) {
       {!allPassed && (
         <div className="rounded-lg border border-warning/20 bg-warning/5 p-3 flex items-start gap-2.5">
           <AlertTriangle className="h-4 w-4 text-warning shrink-0 mt-0.5" />
-          <p className="text-sm text-warning">
-            Resolve all failed checks before starting the migration to avoid
-            errors during export.
-          </p>
+          <p className="text-sm text-warning">{t("guide.resolveFirst")}</p>
         </div>
       )}
 
       {/* Steps */}
       <div className="space-y-2">
-        <h4 className="text-sm font-medium">Migration Steps</h4>
+        <h4 className="text-sm font-medium">{t("guide.steps")}</h4>
 
         <ol className="space-y-1.5">
           <Step
             number={1}
-            title="Download OneNote Md Exporter"
-            description="Get the exporter tool from GitHub. The page includes a detailed guide covering supported features and known limitations."
+            title={t("guide.step1.title")}
+            description={t("guide.step1.description")}
             link={{
               label: "onenote-md-exporter",
               url: "https://github.com/alxnbl/onenote-md-exporter",
@@ -68,18 +68,18 @@ export function MigrationGuide({ allPassed, issueCount }: MigrationGuideProps) {
           />
           <Step
             number={2}
-            title="Prepare your notebooks"
-            description="Launch OneNote and ensure all notebooks you want to export are open and fully synced."
+            title={t("guide.step2.title")}
+            description={t("guide.step2.description")}
           />
           <Step
             number={3}
-            title='Export using "Joplin Raw Directory" format'
-            description="Run the exporter, choose Joplin Raw Directory output, and note the export folder path."
+            title={t("guide.step3.title")}
+            description={t("guide.step3.description")}
           />
           <Step
             number={4}
-            title="Import into Joplin"
-            description='In Joplin, click File &gt; Import &gt; "RAW - Joplin Export Directory" and select the export folder.'
+            title={t("guide.step4.title")}
+            description={t("guide.step4.description")}
           />
         </ol>
       </div>
@@ -0,0 +1,112 @@
+import { useState, type ReactNode } from "react";
+import { ChevronRight, ExternalLink } from "lucide-react";
+import { openUrl } from "@tauri-apps/plugin-opener";
+import { isTauri } from "../../utils/tauri";
+import type { RemediationStep } from "../../stores/types";
+import { useT } from "../../hooks/useT";
+import { cn } from "../../utils/cn";
+
+interface RemediationStepsProps {
+  steps: RemediationStep[];
+}
+
+/** Remediation as a checklist the user can tick off while working through it. */
+export function RemediationSteps({ steps }: RemediationStepsProps) {
+  const [done, setDone] = useState<Set<number>>(new Set());
+
+  const toggle = (index: number) =>
+    setDone((prev) => {
+      const next = new Set(prev);
+      if (!next.delete(index)) next.add(index);
+      return next;
+    });
+
+  return (
+    <ul className="space-y-2">
+      {steps.map((step, i) => (
+        <li key={i} className="flex items-start gap-2 text-sm leading-relaxed">
+          <input
+            type="checkbox"
+            checked={done.has(i)}
+            onChange={() => toggle(i)}
+            className="mt-1 shrink-0 cursor-pointer"
+          />
+          <div className={cn(done.has(i) && "text-muted-foreground line-through")}>
+            <StepContent step={step} />
+          </div>
+        </li>
+      ))}
+    </ul>
+  );
+}
+
+function StepContent({ step }: { step: RemediationStep }) {
+  const t = useT();
+
+  switch (step.kind) {
+    case "instruction":
+      return <TextWithLinks text={step.text} />;
+    case "menuPath":
+      return (
+        <span className="inline-flex flex-wrap items-center gap-1">
+          {t("remediation.goTo")}
+          {step.path.map((item, i) => (
+            <span key={i} className="inline-flex items-center gap-1">
+              {i > 0 && <ChevronRight className="h-3 w-3 text-muted-foreground" />}
+              <span className="font-medium">{item}</span>
+            </span>
+          ))}
+        </span>
+      );
+    case "link":
+      return <ExternalAnchor url={step.url}>{step.label}</ExternalAnchor>;
+    case "command":
+      return (
+        <span>
+          {step.description}:{" "}
+          <code className="rounded bg-secondary px-1.5 py-0.5 font-mono text-xs select-all">
+            {step.command}
+          </code>
+        </span>
+      );
+    case "autoFix":
+      return <span>{t("remediation.autoFix", { description: step.description })}</span>;
+  }
+}
+
+function TextWithLinks({ text }: { text: string }) {
+  const parts = text.split(/(https?:\/\/\S+)/g);
+  return (
+    <>
+      {parts.map((part, i) =>
+        part.match(/^https?:\/\//) ? (
+          <ExternalAnchor key={i} url={part}>
+            {part}
+          </ExternalAnchor>
+        ) : (
+          <span key={i}>{part}</span>
+        )
+      )}
+    </>
+  );
+}
+
+function ExternalAnchor({ url, children }: { url: string; children: ReactNode }) {
+  return (
+    <a
+      href={url}
+      onClick={(e) => {
+        e.preventDefault();
+        if (isTauri()) {
+          openUrl(url);
+        } else {
+          window.open(url, "_blank");
+        }
+      }}
+      className="inline-flex items-center gap-0.5 text-primary hover:underline"
+    >
+      {children}
+      <ExternalLink className="h-3 w-3 inline shrink-0" />
+    </a>
+  );
+}
@@ -1,12 +1,25 @@
 import { RotateCcw, Download } from "lucide-react";
 import { Badge } from "../ui/badge";
 import { Button } from "../ui/button";
-import type { ScanResult } from "../../stores/types";
+import { useT } from "../../hooks/useT";
+import type { ReportFormat, ScanResult } from "../../stores/types";
+import { cn } from "../../utils/cn";
+import { isReportFormat, REPORT_FORMATS } from "../../utils/reportFormats";
+
+function scoreColor(score: number) {
+  if (score >= 90) return "text-success";
+  if (score >= 60) return "text-warning";
+  return "text-destructive-foreground";
+}
 
 interface ResultsSummaryProps {
   result: ScanResult;
   onRescan: () => void;
   onReport: () => void;
+  reportFormat: ReportFormat;
+  onReportFormatChange: (format: ReportFormat) => void;
+  redactReport: boolean;
+  onRedactReportChange: (redact: boolean) => void;
   canSaveReport: boolean;
 }
 
@@ -14,29 +27,46 @@ export function ResultsSummary({
   result,
   onRescan,
   onReport,
+  reportFormat,
+  onReportFormatChange,
+  redactReport,
+  onRedactReportChange,
   canSaveReport,
 }: ResultsSummaryProps) {
   const passCount = result.checks.filter((c) => c.status === "pass").length;
   const failCount = result.checks.filter((c) => c.status === "fail").length;
+  const timeoutCount = result.checks.filter((c) => c.status === "timeout").length;
   const warnCount = result.checks.filter((c) => c.status === "warning").length;
   const skippedCount = result.checks.filter((c) => c.status === "skipped").length;
+  const t = useT();
 
   return (
     <div className="flex items-center justify-between px-4 py-3 border-b border-border">
       <div className="flex items-center gap-3">
         <h2 className="text-base font-semibold whitespace-nowrap">
-          OneNote to Joplin Readiness
+          {t("app.title")}
         </h2>
+        {result.score !== undefined && (
+          <span
+            className={cn("text-sm font-semibold tabular-nums", scoreColor(result.score))}
+            title={t("summary.scoreTitle")}
+          >
+            {result.score}/100
+          </span>
+        )}
         <div className="flex items-center gap-1.5">
           {failCount > 0 && (
-            <Badge variant="destructive">{failCount} Failed</Badge>
+            <Badge variant="destructive">{t("summary.failed", { count: failCount })}</Badge>
+          )}
+          {timeoutCount > 0 && (
+            <Badge variant="destructive">{t("summary.timedOut", { count: timeoutCount })}</Badge>
           )}
           {warnCount > 0 && (
-            <Badge variant="warning">{warnCount} Warning</Badge>
+            <Badge variant="warning">{t("summary.warning", { count: warnCount })}</Badge>
           )}
-          <Badge variant="success">{passCount} Ready</Badge>
+          <Badge variant="success">{t("summary.ready", { count: passCount })}</Badge>
           {skippedCount > 0 && (
-            <Badge variant="secondary">{skippedCount} Skipped</Badge>
+            <Badge variant="secondary">{t("summary.skipped", { count: skippedCount })}</Badge>
           )}
         </div>
       </div>
@@ -48,8 +78,32 @@ export function ResultsSummary({
           className="gap-1.5"
         >
           <RotateCcw className="h-3.5 w-3.5" />
-          Re-Scan
+          {t("common.rescan")}
         </Button>
+        <label
+          className="flex items-center gap-1.5 text-xs text-muted-foreground cursor-pointer"
+          title={t("summary.redactTitle")}
+        >
+          <input
+            type="checkbox"
+            checked={redactReport}
+            onChange={(e) => onRedactReportChange(e.target.checked)}
+          />
+          {t("summary.redact")}
+        </label>
+        <select
+          aria-label={t("summary.reportFormat")}
+          title={t("summary.reportFormat")}
+          value={reportFormat}
+          onChange={(e) => isReportFormat(e.target.value) && onReportFormatChange(e.target.value)}
+          className="rounded-md border border-border bg-transparent px-2 py-1 text-xs"
+        >
+          {Object.entries(REPORT_FORMATS).map(([value, format]) => (
+            <option key={value} value={value}>
+              {format.name}
+            </option>
+          ))}
+        </select>
         <Button
           variant="secondary"
           size="sm"
@@ -58,7 +112,7 @@ export function ResultsSummary({
           className="gap-1.5"
         >
           <Download className="h-3.5 w-3.5" />
-          Report
+          {t("summary.report")}
         </Button>
       </div>
     </div>
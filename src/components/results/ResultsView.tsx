@@ -1,24 +1,30 @@
 import type { ReactNode } from "react";
-import { Rocket } from "lucide-react";
+import { History, Rocket } from "lucide-react";
 import { useReadinessScan } from "../../hooks/useReadinessScan";
 import { useReport } from "../../hooks/useReport";
 import { useWizard } from "../../hooks/useWizard";
+import { useT } from "../../hooks/useT";
 import { useAppStore } from "../../stores/appStore";
 import { ResultsSummary } from "./ResultsSummary";
 import { CheckListItem } from "./CheckListItem";
 import { CheckDetail } from "./CheckDetail";
 import { MigrationGuide } from "./MigrationGuide";
+import { ScanChanges } from "./ScanChanges";
 import type { CheckResult } from "../../stores/types";
 import { cn } from "../../utils/cn";
 
 const NEXT_STEPS_ID = "__next_steps__";
+const CHANGES_ID = "__changes__";
 
 export function ResultsView() {
   const { scanResult, startScan } = useReadinessScan();
-  const { canSave, saveReport } = useReport();
+  const { canSave, saveReport, reportFormat, setReportFormat, redactReport, setRedactReport } =
+    useReport();
   const { failedChecks, enterWizard } = useWizard();
   const selectedCheckId = useAppStore((s) => s.selectedCheckId);
   const selectCheck = useAppStore((s) => s.selectCheck);
+  const scanDiff = useAppStore((s) => s.scanDiff);
+  const t = useT();
 
   if (!scanResult) return null;
 
@@ -26,6 +32,7 @@ export function ResultsView() {
   const selectedCheck =
     scanResult.checks.find((c) => c.id === selectedCheckId) ?? null;
   const allPassed = scanResult.overall === "pass";
+  const shownDiff = selectedCheckId === CHANGES_ID ? scanDiff : null;
   const showMigrationGuide =
     selectedCheckId === NEXT_STEPS_ID || selectedCheckId === null;
 
@@ -35,6 +42,10 @@ export function ResultsView() {
         result={scanResult}
         onRescan={startScan}
         onReport={saveReport}
+        reportFormat={reportFormat}
+        onReportFormatChange={setReportFormat}
+        redactReport={redactReport}
+        onRedactReportChange={setRedactReport}
         canSaveReport={canSave}
       />
 
@@ -44,7 +55,7 @@ export function ResultsView() {
           <div className="flex-1">
             {grouped.fail.length > 0 && (
               <Section
-                label="Failed"
+                label={t("results.failed")}
                 count={grouped.fail.length}
                 variant="fail"
               >
@@ -62,7 +73,7 @@ export function ResultsView() {
             )}
             {grouped.warning.length > 0 && (
               <Section
-                label="Warnings"
+                label={t("results.warnings")}
                 count={grouped.warning.length}
                 variant="warning"
               >
@@ -80,7 +91,7 @@ export function ResultsView() {
             )}
             {grouped.pass.length > 0 && (
               <Section
-                label="Passed"
+                label={t("results.passed")}
                 count={grouped.pass.length}
                 variant="pass"
               >
@@ -98,7 +109,7 @@ export function ResultsView() {
             )}
             {grouped.skipped.length > 0 && (
               <Section
-                label="Skipped"
+                label={t("results.skipped")}
                 count={grouped.skipped.length}
                 variant="skipped"
               >
@@ -116,8 +127,31 @@ export function ResultsView() {
             )}
           </div>
 
-          {/* Next Steps - pinned to bottom */}
+          {/* Changes since the previous scan and Next Steps - pinned to bottom */}
           <div className="border-t border-border">
+            {scanDiff && (
+              <button
+                onClick={() =>
+                  selectCheck(selectedCheckId === CHANGES_ID ? null : CHANGES_ID)
+                }
+                className={cn(
+                  "relative flex items-center gap-2.5 px-3 py-2.5 w-full text-left transition-colors",
+                  "hover:bg-accent/50",
+                  shownDiff &&
+                    "bg-accent before:absolute before:left-0 before:top-0 before:bottom-0 before:w-1 before:rounded-r-sm before:bg-primary"
+                )}
+              >
+                <History className="h-4 w-4 shrink-0 text-muted-foreground" />
+                <div className="min-w-0 flex-1">
+                  <span className="text-sm font-medium block truncate">
+                    {t("changes.title")}
+                  </span>
+                  <p className="text-xs text-muted-foreground truncate mt-0.5">
+                    {t("changes.count", { count: scanDiff.changes.length })}
+                  </p>
+                </div>
+              </button>
+            )}
             <button
               onClick={() =>
                 selectCheck(
@@ -139,10 +173,10 @@ export function ResultsView() {
               />
               <div className="min-w-0 flex-1">
                 <span className="text-sm font-medium block truncate">
-                  Next Steps
+                  {t("results.nextSteps")}
                 </span>
                 <p className="text-xs text-muted-foreground truncate mt-0.5">
-                  Migration guide
+                  {t("results.migrationGuide")}
                 </p>
               </div>
             </button>
@@ -151,7 +185,9 @@ export function ResultsView() {
 
         {/* Detail panel */}
         <div className="overflow-y-auto scrollbar-thin">
-          {showMigrationGuide ? (
+          {shownDiff ? (
+            <ScanChanges diff={shownDiff} />
+          ) : showMigrationGuide ? (
             <MigrationGuide
               allPassed={allPassed}
               issueCount={failedChecks.length}
@@ -177,7 +213,7 @@ export function ResultsView() {
 
 function groupByStatus(checks: CheckResult[]) {
   return {
-    fail: checks.filter((c) => c.status === "fail"),
+    fail: checks.filter((c) => c.status === "fail" || c.status === "timeout"),
     warning: checks.filter((c) => c.status === "warning"),
     pass: checks.filter((c) => c.status === "pass"),
     skipped: checks.filter((c) => c.status === "skipped"),
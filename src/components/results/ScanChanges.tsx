@@ -0,0 +1,89 @@
+import { ArrowRight, History } from "lucide-react";
+import { useAppStore } from "../../stores/appStore";
+import { useT } from "../../hooks/useT";
+import type { ChangeKind, CheckState, ScanDiff } from "../../stores/types";
+import { cn } from "../../utils/cn";
+
+const kindColor: Record<ChangeKind, string> = {
+  added: "text-muted-foreground",
+  removed: "text-muted-foreground",
+  resolved: "text-success",
+  regressed: "text-destructive-foreground",
+  statusChanged: "text-warning",
+  messageChanged: "text-muted-foreground",
+};
+
+/** What changed between an earlier scan and the one on screen. */
+export function ScanChanges({ diff }: { diff: ScanDiff }) {
+  const history = useAppStore((s) => s.history);
+  const locale = useAppStore((s) => s.locale);
+  const compareScans = useAppStore((s) => s.compareScans);
+  const t = useT();
+
+  const formatDate = (timestamp: string) => new Date(timestamp).toLocaleString(locale);
+  // Only scans before the one on screen make sense as a baseline.
+  const earlier = history.filter((s) => s.id < diff.to.id);
+
+  return (
+    <div className="p-5 space-y-4">
+      <div className="flex items-start gap-3">
+        <div className="rounded-full p-2 bg-secondary">
+          <History className="h-5 w-5 text-muted-foreground" />
+        </div>
+        <div className="min-w-0 flex-1">
+          <h3 className="text-base font-semibold">
+            {t("changes.heading", { date: formatDate(diff.from.timestamp) })}
+          </h3>
+          <p className="text-sm text-muted-foreground mt-0.5 tabular-nums">
+            {`${diff.from.score ?? "–"}/100 → ${diff.to.score ?? "–"}/100`}
+          </p>
+        </div>
+        {earlier.length > 1 && (
+          <select
+            aria-label={t("changes.compareWith")}
+            title={t("changes.compareWith")}
+            value={diff.from.id}
+            onChange={(e) => compareScans(e.target.value, diff.to.id)}
+            className="rounded-md border border-border bg-transparent px-2 py-1 text-xs"
+          >
+            {earlier.map((s) => (
+              <option key={s.id} value={s.id}>
+                {`${formatDate(s.timestamp)} · ${s.score ?? "–"}/100`}
+              </option>
+            ))}
+          </select>
+        )}
+      </div>
+
+      {diff.changes.length === 0 ? (
+        <p className="text-sm text-muted-foreground">{t("changes.none")}</p>
+      ) : (
+        <ul className="space-y-2">
+          {diff.changes.map((change) => (
+            <li key={change.id} className="rounded-lg border border-border p-3 space-y-1">
+              <div className="flex items-center justify-between gap-2">
+                <span className="text-sm font-medium">{change.label}</span>
+                <span className={cn("text-xs font-medium", kindColor[change.kind])}>
+                  {t(`changes.kind.${change.kind}`)}
+                </span>
+              </div>
+              <div className="flex items-center gap-1.5 text-xs text-muted-foreground">
+                <StateLabel state={change.before} />
+                <ArrowRight className="h-3 w-3 shrink-0" />
+                <StateLabel state={change.after} />
+              </div>
+              {change.after && change.before?.message !== change.after.message && (
+                <p className="text-xs leading-relaxed">{change.after.message}</p>
+              )}
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
+
+function StateLabel({ state }: { state: CheckState | null }) {
+  const t = useT();
+  return <span>{state ? t(`checkStatus.${state.status}`) : t("changes.notRun")}</span>;
+}
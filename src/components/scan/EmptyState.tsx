@@ -1,9 +1,26 @@
-import { ScanSearch } from "lucide-react";
+import { useEffect } from "react";
+import { FolderOpen, History, ScanSearch } from "lucide-react";
 import { Button } from "../ui/button";
 import { useReadinessScan } from "../../hooks/useReadinessScan";
+import { useT } from "../../hooks/useT";
+import { useAppStore } from "../../stores/appStore";
+import type { ScanSummary } from "../../stores/types";
+import { isTauri } from "../../utils/tauri";
+
+/** Past scans offered on the start screen. */
+const RECENT_SCANS = 5;
 
 export function EmptyState() {
-  const { startScan, scanError } = useReadinessScan();
+  const { startScan, scanError, availableChecks, loadChecks } = useReadinessScan();
+  const history = useAppStore((s) => s.history);
+  const loadHistory = useAppStore((s) => s.loadHistory);
+  const summarizeFleet = useAppStore((s) => s.summarizeFleet);
+  const t = useT();
+
+  useEffect(() => {
+    loadChecks();
+    loadHistory();
+  }, [loadChecks, loadHistory]);
 
   return (
     <div className="flex flex-col items-center gap-6 text-center max-w-md">
@@ -12,24 +29,75 @@ export function EmptyState() {
       </div>
       <div className="space-y-2">
         <h1 className="text-2xl font-semibold tracking-tight">
-          OneNote to Joplin Readiness
+          {t("app.title")}
         </h1>
         <p className="text-muted-foreground text-sm leading-relaxed">
-          Validates your Windows environment before running the OneNote to
-          Joplin migration. Checks Joplin installation, Office versions,
-          desktop applications, and COM automation access.
+          {t("empty.description")}
         </p>
       </div>
+      {availableChecks.length > 0 && (
+        <ul className="flex flex-wrap justify-center gap-1.5">
+          {availableChecks.map((c) => (
+            <li
+              key={c.id}
+              className="rounded-md bg-secondary px-2 py-0.5 text-xs text-muted-foreground"
+            >
+              {c.label}
+            </li>
+          ))}
+        </ul>
+      )}
       {scanError && (
         <p className="text-sm text-destructive-foreground">{scanError}</p>
       )}
       <Button size="lg" onClick={startScan} className="gap-2">
         <ScanSearch className="h-5 w-5" />
-        Run Readiness Scan
+        {t("empty.runScan")}
       </Button>
-      <p className="text-xs text-muted-foreground">
-        Ctrl+R to scan &middot; Ctrl+S to save report
-      </p>
+      <p className="text-xs text-muted-foreground">{t("empty.shortcuts")}</p>
+      {isTauri() && (
+        <Button
+          variant="outline"
+          size="sm"
+          onClick={summarizeFleet}
+          title={t("empty.summarizeFleetTitle")}
+          className="gap-1.5"
+        >
+          <FolderOpen className="h-3.5 w-3.5" />
+          {t("empty.summarizeFleet")}
+        </Button>
+      )}
+      {history.length > 0 && <RecentScans scans={history.slice(0, RECENT_SCANS)} />}
+    </div>
+  );
+}
+
+function RecentScans({ scans }: { scans: ScanSummary[] }) {
+  const openScan = useAppStore((s) => s.openScan);
+  const locale = useAppStore((s) => s.locale);
+  const t = useT();
+
+  return (
+    <div className="w-full space-y-1.5 text-left">
+      <h2 className="flex items-center gap-1.5 text-xs font-semibold uppercase tracking-wider text-muted-foreground">
+        <History className="h-3.5 w-3.5" />
+        {t("history.recent")}
+      </h2>
+      <ul className="rounded-lg border border-border divide-y divide-border">
+        {scans.map((scan) => (
+          <li key={scan.id}>
+            <button
+              onClick={() => openScan(scan.id)}
+              className="flex w-full items-center justify-between gap-3 px-3 py-1.5 text-xs hover:bg-accent/50 transition-colors"
+            >
+              <span>{new Date(scan.timestamp).toLocaleString(locale)}</span>
+              <span className="text-muted-foreground tabular-nums">
+                {`${scan.score ?? "–"}/100 · ${t("history.issues", { count: scan.issues })}`}
+              </span>
+            </button>
+          </li>
+        ))}
+      </ul>
     </div>
   );
 }
@@ -1,15 +1,91 @@
-import { Loader2 } from "lucide-react";
+import {
+  Loader2,
+  CircleCheck,
+  CircleX,
+  AlertTriangle,
+  MinusCircle,
+  TimerOff,
+  Circle,
+} from "lucide-react";
+import { useAppStore } from "../../stores/appStore";
+import { useT } from "../../hooks/useT";
+import { Button } from "../ui/button";
+import type { CheckProgress } from "../../stores/types";
+import { cn } from "../../utils/cn";
+
+const statusIcon = {
+  pass: CircleCheck,
+  fail: CircleX,
+  warning: AlertTriangle,
+  skipped: MinusCircle,
+  timeout: TimerOff,
+};
+
+const statusColor = {
+  pass: "text-success",
+  fail: "text-destructive-foreground",
+  warning: "text-warning",
+  skipped: "text-muted-foreground",
+  timeout: "text-destructive-foreground",
+};
 
 export function ScanningState() {
+  const availableChecks = useAppStore((s) => s.availableChecks);
+  const scanProgress = useAppStore((s) => s.scanProgress);
+  const cancelScan = useAppStore((s) => s.cancelScan);
+  const t = useT();
+
   return (
     <div className="flex flex-col items-center gap-6 text-center">
       <Loader2 className="h-12 w-12 animate-spin text-muted-foreground" />
       <div className="space-y-2">
-        <h2 className="text-xl font-semibold">Scanning Environment</h2>
-        <p className="text-sm text-muted-foreground">
-          Checking Joplin installation, Windows version, Office apps, and COM access...
-        </p>
+        <h2 className="text-xl font-semibold">{t("scanning.title")}</h2>
+        <p className="text-sm text-muted-foreground">{t("scanning.description")}</p>
       </div>
+      {availableChecks.length > 0 && (
+        <ul className="w-72 space-y-1 text-left">
+          {availableChecks.map((c) => (
+            <ProgressRow key={c.id} label={c.label} progress={scanProgress[c.id]} />
+          ))}
+        </ul>
+      )}
+      <Button variant="outline" size="sm" onClick={cancelScan}>
+        {t("common.cancel")}
+      </Button>
     </div>
   );
 }
+
+function ProgressRow({
+  label,
+  progress,
+}: {
+  label: string;
+  progress: CheckProgress | undefined;
+}) {
+  if (progress === undefined) {
+    return (
+      <li className="flex items-center gap-2.5 text-sm text-muted-foreground/60">
+        <Circle className="h-4 w-4 shrink-0" />
+        {label}
+      </li>
+    );
+  }
+
+  if (progress === "running") {
+    return (
+      <li className="flex items-center gap-2.5 text-sm font-medium">
+        <Loader2 className="h-4 w-4 shrink-0 animate-spin text-primary" />
+        {label}
+      </li>
+    );
+  }
+
+  const Icon = statusIcon[progress.status];
+  return (
+    <li className="flex items-center gap-2.5 text-sm">
+      <Icon className={cn("h-4 w-4 shrink-0", statusColor[progress.status])} />
+      {label}
+    </li>
+  );
+}
@@ -1,5 +1,6 @@
 import { ChevronLeft, ChevronRight, RotateCcw, X } from "lucide-react";
 import { Button } from "../ui/button";
+import { useT } from "../../hooks/useT";
 
 interface WizardNavProps {
   isFirst: boolean;
@@ -18,11 +19,13 @@ export function WizardNav({
   onExit,
   onRescan,
 }: WizardNavProps) {
+  const t = useT();
+
   return (
     <div className="flex items-center justify-between">
       <Button variant="ghost" size="sm" onClick={onExit} className="gap-1.5">
         <X className="h-3.5 w-3.5" />
-        Back to Results
+        {t("wizard.back")}
       </Button>
       <div className="flex gap-2">
         <Button
@@ -33,16 +36,16 @@ export function WizardNav({
           className="gap-1"
         >
           <ChevronLeft className="h-3.5 w-3.5" />
-          Prev
+          {t("wizard.prev")}
         </Button>
         {isLast ? (
           <Button size="sm" onClick={onRescan} className="gap-1.5">
             <RotateCcw className="h-3.5 w-3.5" />
-            Re-Scan
+            {t("common.rescan")}
           </Button>
         ) : (
           <Button size="sm" onClick={onNext} className="gap-1">
-            Next
+            {t("wizard.next")}
             <ChevronRight className="h-3.5 w-3.5" />
           </Button>
         )}
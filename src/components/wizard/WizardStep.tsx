@@ -1,7 +1,9 @@
-import { AlertTriangle, CircleX } from "lucide-react";
+import { AlertTriangle, CircleX, TimerOff } from "lucide-react";
 import { Card, CardContent, CardHeader, CardTitle } from "../ui/card";
 import type { CheckResult } from "../../stores/types";
+import { useT } from "../../hooks/useT";
 import { cn } from "../../utils/cn";
+import { RemediationSteps } from "../results/RemediationSteps";
 
 interface WizardStepProps {
   check: CheckResult;
@@ -10,9 +12,10 @@ interface WizardStepProps {
 }
 
 export function WizardStep({ check, stepNumber, totalSteps }: WizardStepProps) {
-  const Icon = check.status === "fail" ? CircleX : AlertTriangle;
-  const iconColor =
-    check.status === "fail" ? "text-destructive-foreground" : "text-warning";
+  const isFailure = check.status === "fail" || check.status === "timeout";
+  const Icon = check.status === "timeout" ? TimerOff : isFailure ? CircleX : AlertTriangle;
+  const iconColor = isFailure ? "text-destructive-foreground" : "text-warning";
+  const t = useT();
 
   return (
     <Card>
@@ -20,16 +23,16 @@ export function WizardStep({ check, stepNumber, totalSteps }: WizardStepProps) {
         <div className="flex items-center gap-2">
           <Icon className={cn("h-5 w-5", iconColor)} />
           <CardTitle className="text-base">
-            Step {stepNumber} of {totalSteps}: {check.label}
+            {t("wizard.step", { number: stepNumber, total: totalSteps, label: check.label })}
           </CardTitle>
         </div>
         <p className="text-sm text-muted-foreground mt-1">{check.message}</p>
       </CardHeader>
       <CardContent>
-        {check.remediation && (
+        {check.remediation.length > 0 && (
           <div className="rounded-lg bg-secondary p-4 text-sm leading-relaxed">
-            <p className="font-medium mb-2">How to fix:</p>
-            <p>{check.remediation}</p>
+            <p className="font-medium mb-2">{t("wizard.howToFix")}</p>
+            <RemediationSteps key={check.id} steps={check.remediation} />
           </div>
         )}
       </CardContent>
@@ -22,10 +22,29 @@ export function useKeyboard() {
         }
       }
 
-      // Escape: Exit wizard
+      // Ctrl+Shift+D: Capture a diagnostics snapshot of the live scan
+      if (e.ctrlKey && e.shiftKey && e.key.toLowerCase() === "d") {
+        e.preventDefault();
+        if (state.view !== "scanning") {
+          state.captureSnapshot();
+        }
+      }
+
+      // Ctrl+O: Replay a diagnostics snapshot
+      if (e.ctrlKey && e.key === "o") {
+        e.preventDefault();
+        if (state.view !== "scanning") {
+          state.replaySnapshot();
+        }
+      }
+
+      // Escape: Exit wizard, or cancel a running scan
       if (e.key === "Escape" && state.view === "wizard") {
         state.exitWizard();
       }
+      if (e.key === "Escape" && state.view === "scanning") {
+        state.cancelScan();
+      }
     };
 
     window.addEventListener("keydown", handler);
@@ -3,6 +3,10 @@ import { useAppStore } from "../stores/appStore";
 export function useReport() {
   const scanResult = useAppStore((s) => s.scanResult);
   const saveReport = useAppStore((s) => s.saveReport);
+  const reportFormat = useAppStore((s) => s.reportFormat);
+  const setReportFormat = useAppStore((s) => s.setReportFormat);
+  const redactReport = useAppStore((s) => s.redactReport);
+  const setRedactReport = useAppStore((s) => s.setRedactReport);
   const canSave = scanResult !== null;
-  return { canSave, saveReport };
+  return { canSave, saveReport, reportFormat, setReportFormat, redactReport, setRedactReport };
 }
@@ -3,5 +3,7 @@ import { useAppStore } from "../stores/appStore";
 export function useStatusBar() {
   const statusMessage = useAppStore((s) => s.statusMessage);
   const statusType = useAppStore((s) => s.statusType);
-  return { statusMessage, statusType };
+  const locale = useAppStore((s) => s.locale);
+  const setLocale = useAppStore((s) => s.setLocale);
+  return { statusMessage, statusType, locale, setLocale };
 }
@@ -0,0 +1,12 @@
+import { useCallback } from "react";
+import { useAppStore } from "../stores/appStore";
+import { translate, type MessageKey, type Params } from "../i18n";
+
+/** `translate` bound to the app's current language. */
+export function useT() {
+  const locale = useAppStore((s) => s.locale);
+  return useCallback(
+    (key: MessageKey, params?: Params) => translate(locale, key, params),
+    [locale]
+  );
+}
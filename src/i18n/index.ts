@@ -0,0 +1,42 @@
+import en from "./en.json";
+import nl from "./nl.json";
+import de from "./de.json";
+import type { Locale } from "../stores/types";
+
+export type MessageKey = keyof typeof en;
+
+export type Params = Record<string, string | number>;
+
+export const LOCALES: { code: Locale; name: string }[] = [
+  { code: "en", name: "English" },
+  { code: "nl", name: "Nederlands" },
+  { code: "de", name: "Deutsch" },
+];
+
+const catalogs: Record<Locale, Record<string, string>> = { en, nl, de };
+
+export function isLocale(value: string | null | undefined): value is Locale {
+  return LOCALES.some((l) => l.code === value);
+}
+
+/** First of the browser's preferred languages that has a catalog. */
+export function browserLocale(): Locale {
+  const code = navigator.languages
+    .map((tag) => tag.split("-")[0].toLowerCase())
+    .find(isLocale);
+  return code ?? "en";
+}
+
+/**
+ * The catalog entry for `key` with `{name}` placeholders filled in. A
+ * `count` param selects the `.one` variant for exactly one. Falls back to
+ * English, then to the key itself.
+ */
+export function translate(locale: Locale, key: MessageKey, params: Params = {}): string {
+  const lookup = (k: string) => catalogs[locale][k] ?? catalogs.en[k];
+  const template =
+    (params.count === 1 ? lookup(`${key}.one`) : undefined) ?? lookup(key) ?? key;
+  return template.replace(/\{(\w+)\}/g, (match, name: string) =>
+    name in params ? String(params[name]) : match
+  );
+}
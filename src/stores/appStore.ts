@@ -1,69 +1,301 @@
 import { create } from "zustand";
-import type { AppState, ScanResult } from "./types";
+import type {
+  AppState,
+  CheckInfo,
+  CheckResult,
+  FixOutcome,
+  Locale,
+  ReportFormat,
+  ScanDiff,
+  ScanResult,
+  ScanSummary,
+} from "./types";
 import { isTauri } from "../utils/tauri";
+import { FLEET_FORMATS, fleetFormatOf, isReportFormat, REPORT_FORMATS } from "../utils/reportFormats";
+import { browserLocale, isLocale, translate, type MessageKey, type Params } from "../i18n";
+
+/** Language the user picked in the app; unset means follow Windows. */
+const LOCALE_KEY = "locale";
+
+function storedLocale() {
+  const value = localStorage.getItem(LOCALE_KEY);
+  return isLocale(value) ? value : null;
+}
+
+const REPORT_FORMAT_KEY = "reportFormat";
+const REDACT_REPORT_KEY = "redactReport";
+
+function storedReportFormat(): ReportFormat {
+  const value = localStorage.getItem(REPORT_FORMAT_KEY);
+  return isReportFormat(value) ? value : "markdown";
+}
 
 const mockScanResult: ScanResult = {
   checks: [
-    { id: "joplin", label: "Joplin", status: "pass", message: "Joplin 3.1.24 found at C:\\Users\\User\\AppData\\Local\\Programs\\joplin", remediation: null },
-    { id: "windows_os", label: "Windows OS", status: "pass", message: "Windows 11 Enterprise 23H2 (Build 22631.4890)", remediation: null },
-    { id: "onenote", label: "OneNote (Desktop)", status: "pass", message: "Version 16.0 found — COM automation verified.", remediation: null },
-    { id: "word", label: "Word", status: "fail", message: "Word desktop not found", remediation: "Install Microsoft Office with Word included." },
-    { id: "sync_auto", label: "OneNote Auto-Sync", status: "skipped", message: "Skipped \u2014 OneNote Desktop not installed", remediation: null },
-    { id: "sync_download", label: "OneNote Full Download", status: "skipped", message: "Skipped \u2014 OneNote Desktop not installed", remediation: null },
+    { id: "joplin", label: "Joplin", status: "pass", message: "Joplin 3.1.24 found at C:\\Users\\User\\AppData\\Local\\Programs\\joplin", remediation: [], evidence: [{ key: "InstallLocation", value: "C:\\Users\\User\\AppData\\Local\\Programs\\joplin", source: "HKCU\\SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall\\Joplin" }], durationMs: 14, severity: "blocker", weight: 40 },
+    { id: "windows_os", label: "Windows OS", status: "pass", message: "Windows 11 Enterprise 23H2 (Build 22631.4890)", remediation: [], evidence: [{ key: "CurrentBuild", value: "22631", source: "HKLM\\SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion" }, { key: "UBR", value: "4890", source: "HKLM\\SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion" }], durationMs: 2, severity: "major", weight: 20 },
+    { id: "onenote", label: "OneNote (Desktop)", status: "pass", message: "Version 16.0 found — COM automation verified.", remediation: [], evidence: [{ key: "Path", value: "C:\\Program Files\\Microsoft Office\\root\\Office16\\", source: "HKLM\\SOFTWARE\\Microsoft\\Office\\16.0\\OneNote\\InstallRoot" }, { key: "OneNote.Application", value: "activated", source: "COM" }], durationMs: 1840, severity: "blocker", weight: 40 },
+    { id: "word", label: "Word", status: "fail", message: "Word desktop not found", remediation: [{ kind: "instruction", text: "Install Microsoft Office (Desktop) with Word included. Office 2013 or later is required." }], evidence: [{ key: "Word.Application", value: "CLSIDFromProgID: Invalid class string", source: "COM" }], durationMs: 35, severity: "major", weight: 20 },
+    { id: "sync_auto", label: "OneNote Auto-Sync", status: "pass", message: "Automatic sync is enabled (Office 16.0)", remediation: [], evidence: [{ key: "SyncStateOffline", value: "0", source: "HKCU\\Software\\Microsoft\\Office\\16.0\\OneNote\\Options\\Save" }], durationMs: 1, severity: "minor", weight: 10 },
+    { id: "sync_download", label: "OneNote Full Download", status: "warning", message: "Full file and image download is not enabled (Office 16.0)", remediation: [{ kind: "menuPath", path: ["OneNote", "File", "Options", "Sync"] }, { kind: "instruction", text: "Enable 'Download all files and images'." }, { kind: "autoFix", checkId: "sync_download", description: "Turn on 'Download all files and images' in OneNote (set DeferFdoDownload to 0)." }], evidence: [{ key: "DeferFdoDownload", value: "(not set)", source: "HKCU\\Software\\Microsoft\\Office\\16.0\\OneNote\\Options" }], durationMs: 1, severity: "major", weight: 20 },
+    { id: "disk_space", label: "Disk Space", status: "pass", message: "Enough space for at least 2.4 GB of notebooks: C:\\ has 112.7 GB free and needs 4.8 GB for the export folder and the Joplin profile — the estimate grows once OneNote downloads all files and images", remediation: [], evidence: [{ key: "OneNoteCache", value: "2576980378", source: "C:\\Users\\User\\AppData\\Local\\Microsoft\\OneNote\\16.0\\cache" }, { key: "FreeSpace", value: "121011232768", source: "C:\\Users\\User\\Documents" }, { key: "FreeSpace", value: "121011232768", source: "C:\\Users\\User\\AppData\\Roaming\\joplin-desktop" }], durationMs: 412, severity: "major", weight: 20 },
+    { id: "locked_sections", label: "Locked Sections", status: "warning", message: "These sections are likely to be skipped by the export: Personal: Journal (password-protected, locked)", remediation: [{ kind: "instruction", text: "Before you migrate, open each password-protected section in OneNote and unlock it with its password. Sections lock again after a while, so remove the password to be sure they are exported:" }, { kind: "menuPath", path: ["OneNote", "Review", "Password"] }], evidence: [{ key: "Notebooks", value: "2", source: "COM" }, { key: "Sections", value: "14", source: "COM" }, { key: "Pages", value: "312", source: "COM" }, { key: "encrypted", value: "true", source: "C:\\Users\\User\\Documents\\OneNote Notebooks\\Personal\\Journal.one" }, { key: "isLocked", value: "true", source: "C:\\Users\\User\\Documents\\OneNote Notebooks\\Personal\\Journal.one" }], durationMs: 2210, severity: "major", weight: 20 },
   ],
   timestamp: new Date().toISOString(),
+  durationMs: 2236,
   osInfo: "Windows 11 Enterprise 23H2 (Build 22631.4890)",
   overall: "fail",
+  score: 80,
+  locale: "en",
 };
 
+const mockChecks: CheckInfo[] = [
+  { id: "joplin", label: "Joplin Desktop", category: "joplin", dependsOn: [], severity: "blocker", weight: 40 },
+  { id: "windows_os", label: "Windows OS", category: "system", dependsOn: [], severity: "major", weight: 20 },
+  { id: "onenote", label: "OneNote (Desktop)", category: "office", dependsOn: [], severity: "blocker", weight: 40 },
+  { id: "word", label: "Word", category: "office", dependsOn: [], severity: "major", weight: 20 },
+  { id: "sync_auto", label: "OneNote Auto-Sync", category: "sync", dependsOn: ["onenote"], severity: "minor", weight: 10 },
+  { id: "sync_download", label: "OneNote Full Download", category: "sync", dependsOn: ["onenote"], severity: "major", weight: 20 },
+  { id: "disk_space", label: "Disk Space", category: "system", dependsOn: ["windows_os"], severity: "major", weight: 20 },
+  { id: "locked_sections", label: "Locked Sections", category: "office", dependsOn: ["onenote"], severity: "major", weight: 20 },
+];
+
+/** A status bar message in the app's current language. */
+const t = (key: MessageKey, params?: Params) =>
+  translate(useAppStore.getState().locale, key, params);
+
+function resultState(result: ScanResult) {
+  const failed = result.checks.filter((c) => c.status !== "pass" && c.status !== "skipped");
+  const firstIssue = failed.length > 0 ? failed[0].id : null;
+  return {
+    view: "results" as const,
+    scanResult: result,
+    failedChecks: failed,
+    selectedCheckId: firstIssue,
+    statusMessage:
+      result.overall === "pass"
+        ? t("statusBar.allPassed")
+        : t("statusBar.issuesFound", { count: failed.length }),
+    statusType: result.overall === "pass" ? ("success" as const) : ("error" as const),
+  };
+}
+
+/**
+ * After a live scan, find it in the history and compare it with the scan
+ * before. If it could not be stored, there is nothing to compare.
+ */
+async function trackLatestScan(result: ScanResult) {
+  const { loadHistory, compareScans } = useAppStore.getState();
+  await loadHistory();
+  const [latest, previous] = useAppStore.getState().history;
+  const stored = latest?.timestamp === result.timestamp;
+  useAppStore.setState({ currentScanId: stored ? latest.id : null, scanDiff: null });
+  if (stored && previous) {
+    await compareScans(previous.id, latest.id);
+  }
+}
+
+const initialLocale = storedLocale() ?? browserLocale();
+
 export const useAppStore = create<AppState>((set, get) => ({
   view: "empty",
+  availableChecks: [],
+  scanProgress: {},
   scanResult: null,
   scanError: null,
   wizardStep: 0,
   failedChecks: [],
   selectedCheckId: null,
-  statusMessage: "Ready",
+  statusMessage: translate(initialLocale, "statusBar.ready"),
   statusType: "info",
+  fixesApplied: false,
+  locale: initialLocale,
+  history: [],
+  currentScanId: null,
+  scanDiff: null,
+  reportFormat: storedReportFormat(),
+  redactReport: localStorage.getItem(REDACT_REPORT_KEY) === "true",
+
+  loadLocale: async () => {
+    if (!isTauri()) return;
+    const { invoke } = await import("@tauri-apps/api/core");
+    const stored = storedLocale();
+    if (stored) {
+      await invoke("set_locale", { locale: stored });
+    } else {
+      const locale = await invoke<Locale>("get_locale");
+      set({ locale, statusMessage: translate(locale, "statusBar.ready") });
+    }
+    await get().loadChecks();
+  },
+
+  setLocale: async (locale) => {
+    localStorage.setItem(LOCALE_KEY, locale);
+    set({
+      locale,
+      statusMessage: translate(locale, get().scanResult ? "statusBar.languageChanged" : "statusBar.ready"),
+      statusType: "info",
+    });
+    if (!isTauri()) return;
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("set_locale", { locale });
+    await get().loadChecks();
+  },
+
+  loadChecks: async () => {
+    if (isTauri()) {
+      const { invoke } = await import("@tauri-apps/api/core");
+      try {
+        const checks = await invoke<CheckInfo[]>("list_checks");
+        set({ availableChecks: checks });
+      } catch (e) {
+        // An invalid policy file; the scan reports the same error.
+        set({ availableChecks: [], statusMessage: String(e), statusType: "error" });
+      }
+    } else {
+      set({ availableChecks: mockChecks });
+    }
+  },
 
   startScan: async () => {
     set({
       view: "scanning",
+      scanProgress: {},
       scanError: null,
-      statusMessage: "Scanning...",
+      statusMessage: t("statusBar.scanning"),
       statusType: "info",
     });
 
+    const onStarted = (info: CheckInfo) =>
+      set((s) => ({
+        scanProgress: { ...s.scanProgress, [info.id]: "running" },
+        statusMessage: t("statusBar.checking", { label: info.label }),
+      }));
+    const onFinished = (check: CheckResult) =>
+      set((s) => ({ scanProgress: { ...s.scanProgress, [check.id]: check } }));
+
+    // Set when the scan succeeded but could not be stored in the history.
+    let historyError = null as string | null;
+
     try {
       let result: ScanResult;
 
       if (isTauri()) {
         const { invoke } = await import("@tauri-apps/api/core");
-        result = await invoke<ScanResult>("run_readiness_scan");
+        const { listen } = await import("@tauri-apps/api/event");
+        const unlisten = await Promise.all([
+          listen<CheckInfo>("scan://check-started", (e) => onStarted(e.payload)),
+          listen<CheckResult>("scan://check-finished", (e) => onFinished(e.payload)),
+          listen<string>("history://record-failed", (e) => (historyError = e.payload)),
+        ]);
+        try {
+          result = await invoke<ScanResult>("run_readiness_scan");
+        } finally {
+          unlisten.forEach((u) => u());
+        }
       } else {
         // Mock for browser dev
-        await new Promise((r) => setTimeout(r, 1500));
+        for (const check of mockScanResult.checks) {
+          onStarted(mockChecks.find((c) => c.id === check.id)!);
+          await new Promise((r) => setTimeout(r, 250));
+          onFinished(check);
+        }
         result = mockScanResult;
       }
 
-      const failed = result.checks.filter((c) => c.status !== "pass" && c.status !== "skipped");
-      const firstIssue = failed.length > 0 ? failed[0].id : null;
+      set({ ...resultState(result), currentScanId: null, scanDiff: null });
+      if (historyError) {
+        set({ statusMessage: t("statusBar.historyFailed", { error: historyError }), statusType: "error" });
+      }
+      if (isTauri()) await trackLatestScan(result);
+    } catch (err) {
+      if (String(err) === "Scan cancelled") {
+        set({ view: "empty", statusMessage: t("statusBar.scanCancelled"), statusType: "info" });
+        return;
+      }
+      set({
+        view: "empty",
+        scanError: String(err),
+        statusMessage: t("statusBar.scanFailed", { error: String(err) }),
+        statusType: "error",
+      });
+    }
+  },
+
+  cancelScan: async () => {
+    if (!isTauri()) return;
+    const { invoke } = await import("@tauri-apps/api/core");
+    set({ statusMessage: t("statusBar.cancelling"), statusType: "info" });
+    await invoke("cancel_scan");
+  },
+
+  captureSnapshot: async () => {
+    if (!isTauri()) return;
+    const { save } = await import("@tauri-apps/plugin-dialog");
+    const { invoke } = await import("@tauri-apps/api/core");
+    const path = await save({
+      defaultPath: "readiness-snapshot.json",
+      filters: [{ name: "Snapshot", extensions: ["json"] }],
+    });
+    if (!path) return;
+
+    set({
+      view: "scanning",
+      scanProgress: {},
+      scanError: null,
+      statusMessage: t("statusBar.capturing"),
+      statusType: "info",
+    });
+    const { listen } = await import("@tauri-apps/api/event");
+    let historyError = null as string | null;
+    const unlisten = await listen<string>("history://record-failed", (e) => (historyError = e.payload));
+    try {
+      const result = await invoke<ScanResult>("capture_snapshot", { path });
       set({
-        view: "results",
-        scanResult: result,
-        failedChecks: failed,
-        selectedCheckId: firstIssue,
-        statusMessage:
-          result.overall === "pass"
-            ? "All checks passed"
-            : `${failed.length} issue(s) found`,
-        statusType: result.overall === "pass" ? "success" : "error",
+        ...resultState(result),
+        statusMessage: historyError
+          ? t("statusBar.historyFailed", { error: historyError })
+          : t("statusBar.snapshotSaved"),
       });
+      await trackLatestScan(result);
     } catch (err) {
+      if (String(err) === "Scan cancelled") {
+        set({ view: "empty", statusMessage: t("statusBar.scanCancelled"), statusType: "info" });
+        return;
+      }
       set({
         view: "empty",
         scanError: String(err),
-        statusMessage: `Scan failed: ${err}`,
+        statusMessage: t("statusBar.snapshotFailed", { error: String(err) }),
+        statusType: "error",
+      });
+    } finally {
+      unlisten();
+    }
+  },
+
+  replaySnapshot: async () => {
+    if (!isTauri()) return;
+    const { open } = await import("@tauri-apps/plugin-dialog");
+    const { invoke } = await import("@tauri-apps/api/core");
+    const path = await open({
+      multiple: false,
+      filters: [{ name: "Snapshot", extensions: ["json"] }],
+    });
+    if (!path) return;
+
+    try {
+      const result = await invoke<ScanResult>("replay_snapshot", { path });
+      set({
+        ...resultState(result),
+        currentScanId: null,
+        scanDiff: null,
+        statusMessage: t("statusBar.replayed"),
+      });
+    } catch (err) {
+      set({
+        scanError: String(err),
+        statusMessage: t("statusBar.replayFailed", { error: String(err) }),
         statusType: "error",
       });
     }
@@ -75,10 +307,90 @@ export const useAppStore = create<AppState>((set, get) => ({
       scanResult: null,
       scanError: null,
       selectedCheckId: null,
-      statusMessage: "Ready",
+      currentScanId: null,
+      scanDiff: null,
+      statusMessage: t("statusBar.ready"),
       statusType: "info",
     }),
 
+  fixCheck: async (id, dryRun) => {
+    if (!isTauri()) return null;
+    const { invoke } = await import("@tauri-apps/api/core");
+    try {
+      const outcome = await invoke<FixOutcome>("fix_check", { id, dryRun });
+      const rerun = outcome.result;
+      if (rerun) {
+        set((s) => ({
+          fixesApplied: s.fixesApplied || !outcome.change.unchanged,
+          scanResult: s.scanResult && {
+            ...s.scanResult,
+            checks: s.scanResult.checks.map((c) => (c.id === rerun.id ? rerun : c)),
+          },
+          statusMessage: t("statusBar.fixApplied"),
+          statusType: "success",
+        }));
+      }
+      return outcome;
+    } catch (err) {
+      set({ statusMessage: t("statusBar.fixFailed", { error: String(err) }), statusType: "error" });
+      return null;
+    }
+  },
+
+  revertFixes: async () => {
+    if (!isTauri()) return;
+    const { invoke } = await import("@tauri-apps/api/core");
+    try {
+      const rerun = await invoke<CheckResult[]>("revert_fixes");
+      set((s) => ({
+        fixesApplied: false,
+        scanResult: s.scanResult && {
+          ...s.scanResult,
+          checks: s.scanResult.checks.map((c) => rerun.find((r) => r.id === c.id) ?? c),
+        },
+        statusMessage: t("statusBar.fixesReverted", { count: rerun.length }),
+        statusType: "success",
+      }));
+    } catch (err) {
+      set({ statusMessage: t("statusBar.undoFailed", { error: String(err) }), statusType: "error" });
+    }
+  },
+
+  loadHistory: async () => {
+    if (!isTauri()) return;
+    const { invoke } = await import("@tauri-apps/api/core");
+    try {
+      set({ history: await invoke<ScanSummary[]>("list_scans") });
+    } catch {
+      // Without a readable history the app works as before.
+      set({ history: [] });
+    }
+  },
+
+  openScan: async (id) => {
+    if (!isTauri()) return;
+    const { invoke } = await import("@tauri-apps/api/core");
+    try {
+      const result = await invoke<ScanResult>("get_scan", { id });
+      set({ ...resultState(result), currentScanId: id, scanDiff: null });
+      const { history } = get();
+      const previous = history[history.findIndex((s) => s.id === id) + 1];
+      if (previous) await get().compareScans(previous.id, id);
+    } catch (err) {
+      set({ statusMessage: t("statusBar.openScanFailed", { error: String(err) }), statusType: "error" });
+    }
+  },
+
+  compareScans: async (from, to) => {
+    if (!isTauri()) return;
+    const { invoke } = await import("@tauri-apps/api/core");
+    try {
+      set({ scanDiff: await invoke<ScanDiff>("diff_scans", { a: from, b: to }) });
+    } catch (err) {
+      set({ statusMessage: t("statusBar.compareFailed", { error: String(err) }), statusType: "error" });
+    }
+  },
+
   selectCheck: (id) => set({ selectedCheckId: id }),
 
   enterWizard: () => set({ view: "wizard", wizardStep: 0 }),
@@ -99,45 +411,95 @@ export const useAppStore = create<AppState>((set, get) => ({
     }
   },
 
-  generateReport: async () => {
-    const { scanResult } = get();
+  setReportFormat: (format) => {
+    localStorage.setItem(REPORT_FORMAT_KEY, format);
+    set({ reportFormat: format });
+  },
+
+  setRedactReport: (redact) => {
+    localStorage.setItem(REDACT_REPORT_KEY, String(redact));
+    set({ redactReport: redact });
+  },
+
+  generateReport: async (format = get().reportFormat) => {
+    const { scanResult, redactReport } = get();
     if (!scanResult) throw new Error("No scan result");
 
     if (isTauri()) {
       const { invoke } = await import("@tauri-apps/api/core");
-      return invoke<string>("generate_report", { results: scanResult });
+      return invoke<string>("generate_report", { results: scanResult, format, redact: redactReport });
     }
 
     // Mock for browser dev
-    return `# Readiness Report\n\nGenerated: ${scanResult.timestamp}\n\n${scanResult.checks
+    if (format === "json") {
+      return JSON.stringify({ schemaVersion: 1, scan: scanResult }, null, 2);
+    }
+    const markdown = `# Readiness Report\n\nGenerated: ${scanResult.timestamp}\n\n${scanResult.checks
       .map((c) => `- ${c.label}: ${c.status.toUpperCase()} - ${c.message}`)
       .join("\n")}`;
+    if (format === "html") {
+      const pre = document.createElement("pre");
+      pre.textContent = markdown;
+      return `<!DOCTYPE html>\n<meta charset="utf-8">\n<title>Readiness Report</title>\n${pre.outerHTML}`;
+    }
+    return markdown;
   },
 
   saveReport: async () => {
-    const markdown = await get().generateReport();
+    const { reportFormat } = get();
+    const format = REPORT_FORMATS[reportFormat];
+    const report = await get().generateReport(reportFormat);
+    const fileName = `readiness-report.${format.extension}`;
 
     if (isTauri()) {
       const { save } = await import("@tauri-apps/plugin-dialog");
       const { invoke } = await import("@tauri-apps/api/core");
       const path = await save({
-        defaultPath: "readiness-report.md",
-        filters: [{ name: "Markdown", extensions: ["md"] }],
+        defaultPath: fileName,
+        filters: [{ name: format.name, extensions: [format.extension] }],
       });
       if (path) {
-        await invoke("save_report", { markdown, path });
-        set({ statusMessage: "Report saved", statusType: "success" });
+        await invoke("save_report", { report, path, format: reportFormat });
+        set({ statusMessage: t("statusBar.reportSaved"), statusType: "success" });
       }
     } else {
       // Browser fallback: download as file
-      const blob = new Blob([markdown], { type: "text/markdown" });
+      const blob = new Blob([report], { type: format.mime });
       const url = URL.createObjectURL(blob);
       const a = document.createElement("a");
       a.href = url;
-      a.download = "readiness-report.md";
+      a.download = fileName;
       a.click();
       URL.revokeObjectURL(url);
-      set({ statusMessage: "Report downloaded", statusType: "success" });
+      set({ statusMessage: t("statusBar.reportDownloaded"), statusType: "success" });
+    }
+  },
+
+  summarizeFleet: async () => {
+    if (!isTauri()) return;
+    const { open, save } = await import("@tauri-apps/plugin-dialog");
+    const { invoke } = await import("@tauri-apps/api/core");
+    const folder = await open({ directory: true, multiple: false });
+    if (!folder) return;
+    const path = await save({
+      defaultPath: "fleet-summary.md",
+      filters: Object.values(FLEET_FORMATS).map((f) => ({ name: f.name, extensions: [f.extension] })),
+    });
+    if (!path) return;
+
+    set({ statusMessage: t("statusBar.summarizingFleet"), statusType: "info" });
+    try {
+      const count = await invoke<number>("summarize_fleet", {
+        folder,
+        path,
+        format: fleetFormatOf(path),
+      });
+      set({ statusMessage: t("statusBar.fleetSummarized", { count }), statusType: "success" });
+    } catch (err) {
+      set({
+        statusMessage: t("statusBar.fleetSummaryFailed", { error: String(err) }),
+        statusType: "error",
+      });
     }
   },
 }));
@@ -1,26 +1,182 @@
-export type CheckStatus = "pass" | "fail" | "warning" | "skipped";
+export type CheckStatus = "pass" | "fail" | "warning" | "skipped" | "timeout";
+
+export type CheckCategory = "joplin" | "system" | "office" | "sync" | "policy";
+
+export type Severity = "blocker" | "major" | "minor" | "info";
+
+export interface CheckInfo {
+  id: string;
+  label: string;
+  category: CheckCategory;
+  dependsOn: string[];
+  severity: Severity;
+  weight: number;
+}
+
+/** A registry value, file or COM probe a check based its verdict on. */
+export interface Evidence {
+  key: string;
+  value: string;
+  source: string;
+}
+
+/** One step of the advice for a check that did not pass. */
+export type RemediationStep =
+  | { kind: "instruction"; text: string }
+  | { kind: "menuPath"; path: string[] }
+  | { kind: "link"; label: string; url: string }
+  | { kind: "command"; command: string; description: string }
+  | { kind: "autoFix"; checkId: string; description: string };
 
 export interface CheckResult {
   id: string;
   label: string;
   status: CheckStatus;
   message: string;
-  remediation: string | null;
+  remediation: RemediationStep[];
+  evidence: Evidence[];
+  durationMs: number;
+  severity: Severity;
+  weight: number;
+}
+
+export type RegValue =
+  | { type: "Sz"; data: string }
+  | { type: "Dword"; data: number }
+  | { type: "Qword"; data: number }
+  | { type: "MultiSz"; data: string[] };
+
+export interface RegistryFix {
+  hive: "HKLM" | "HKCU";
+  path: string;
+  name: string;
+  value: RegValue;
+  description: string;
+}
+
+export interface FixChange {
+  checkId: string;
+  fix: RegistryFix;
+  previous: RegValue | null;
+  unchanged: boolean;
+}
+
+export interface FixOutcome {
+  change: FixChange;
+  /** The check rerun after the fix; null for a dry run. */
+  result: CheckResult | null;
+}
+
+export interface Page {
+  id: string;
+  name: string;
+  lastModified?: string;
+}
+
+export interface Section {
+  id: string;
+  name: string;
+  /** The `.one` file holding the section. */
+  path: string;
+  lastModified?: string;
+  /** Password-protected and locked right now. */
+  isLocked?: boolean;
+  /** Password-protected, even if unlocked for the moment. */
+  encrypted?: boolean;
+  readOnly?: boolean;
+  pages: Page[];
+}
+
+/** A notebook or a section group inside one. */
+export interface SectionGroup {
+  id: string;
+  name: string;
+  path: string;
+  lastModified?: string;
+  sectionGroups: SectionGroup[];
+  sections: Section[];
+}
+
+export type Notebook = SectionGroup;
+
+/** Notebooks OneNote has open, from its hierarchy. */
+export interface Inventory {
+  notebooks: Notebook[];
 }
 
 export interface ScanResult {
   checks: CheckResult[];
   timestamp: string;
+  /** Wall-clock time of the whole scan; checks run in parallel. */
+  durationMs?: number;
   osInfo: string;
   overall: CheckStatus;
+  /** Weighted readiness, 0–100. Absent when no weighted check ran. */
+  score?: number;
+  /** Language the messages and remediation are written in. */
+  locale: Locale;
+  /** Personal details were replaced by placeholders; only set on reports. */
+  redacted?: boolean;
+  /** Absent if the scan did not read the notebooks from OneNote. */
+  inventory?: Inventory;
+}
+
+/** A scan kept in the local history, as listed by `list_scans`. */
+export interface ScanSummary {
+  id: string;
+  timestamp: string;
+  overall: CheckStatus;
+  score?: number;
+  /** Checks that failed, timed out or warned. */
+  issues: number;
+}
+
+export type ChangeKind =
+  | "added"
+  | "removed"
+  | "resolved"
+  | "regressed"
+  | "statusChanged"
+  | "messageChanged";
+
+export interface CheckState {
+  status: CheckStatus;
+  message: string;
+}
+
+export interface CheckChange {
+  id: string;
+  label: string;
+  kind: ChangeKind;
+  /** null if the check did not run in that scan. */
+  before: CheckState | null;
+  after: CheckState | null;
 }
 
+export interface ScanDiff {
+  from: ScanSummary;
+  to: ScanSummary;
+  changes: CheckChange[];
+}
+
+/** Live state of a check during a scan: running, or its finished result. */
+export type CheckProgress = "running" | CheckResult;
+
 export type AppView = "empty" | "scanning" | "results" | "wizard";
 
 export type StatusType = "info" | "error" | "success";
 
+export type Locale = "en" | "nl" | "de";
+
+export type ReportFormat = "markdown" | "json" | "html" | "junit" | "sarif";
+
+/** Formats `summarize_fleet` writes. */
+export type FleetFormat = "markdown" | "html" | "csv";
+
 export interface AppState {
   view: AppView;
+  availableChecks: CheckInfo[];
+  scanProgress: Record<string, CheckProgress>;
   scanResult: ScanResult | null;
   scanError: string | null;
   wizardStep: number;
@@ -28,14 +184,39 @@ export interface AppState {
   selectedCheckId: string | null;
   statusMessage: string;
   statusType: StatusType;
+  fixesApplied: boolean;
+  locale: Locale;
+  /** Stored scans, newest first. */
+  history: ScanSummary[];
+  /** History id of the scan on screen; null for replays and the browser mock. */
+  currentScanId: string | null;
+  scanDiff: ScanDiff | null;
+  reportFormat: ReportFormat;
+  /** Replace the user profile folder, user and machine names in saved reports. */
+  redactReport: boolean;
 
+  loadLocale: () => Promise<void>;
+  setLocale: (locale: Locale) => Promise<void>;
+  loadChecks: () => Promise<void>;
   startScan: () => Promise<void>;
+  cancelScan: () => Promise<void>;
+  captureSnapshot: () => Promise<void>;
+  replaySnapshot: () => Promise<void>;
   resetScan: () => void;
+  fixCheck: (id: string, dryRun: boolean) => Promise<FixOutcome | null>;
+  revertFixes: () => Promise<void>;
+  loadHistory: () => Promise<void>;
+  openScan: (id: string) => Promise<void>;
+  compareScans: (from: string, to: string) => Promise<void>;
   selectCheck: (id: string | null) => void;
   enterWizard: () => void;
   exitWizard: () => void;
   nextWizardStep: () => void;
   prevWizardStep: () => void;
-  generateReport: () => Promise<string>;
+  setReportFormat: (format: ReportFormat) => void;
+  setRedactReport: (redact: boolean) => void;
+  generateReport: (format?: ReportFormat) => Promise<string>;
   saveReport: () => Promise<void>;
+  /** Pick a folder of JSON reports and save a summary across the machines. */
+  summarizeFleet: () => Promise<void>;
 }
@@ -0,0 +1,28 @@
+import type { FleetFormat, ReportFormat } from "../stores/types";
+
+/** How each format `generate_report` supports is offered and saved. */
+export const REPORT_FORMATS: Record<ReportFormat, { name: string; extension: string; mime: string }> = {
+  markdown: { name: "Markdown", extension: "md", mime: "text/markdown" },
+  json: { name: "JSON", extension: "json", mime: "application/json" },
+  html: { name: "HTML", extension: "html", mime: "text/html" },
+  junit: { name: "JUnit XML", extension: "xml", mime: "application/xml" },
+  sarif: { name: "SARIF", extension: "sarif", mime: "application/sarif+json" },
+};
+
+export function isReportFormat(value: string | null): value is ReportFormat {
+  return value !== null && value in REPORT_FORMATS;
+}
+
+/** Formats `summarize_fleet` writes, offered as filters of one save dialog. */
+export const FLEET_FORMATS: Record<FleetFormat, { name: string; extension: string }> = {
+  markdown: { name: "Markdown", extension: "md" },
+  html: { name: "HTML", extension: "html" },
+  csv: { name: "CSV", extension: "csv" },
+};
+
+/** The fleet format a saved path asks for by its extension; Markdown otherwise. */
+export function fleetFormatOf(path: string): FleetFormat {
+  const extension = path.split(".").pop()?.toLowerCase();
+  const entry = Object.entries(FLEET_FORMATS).find(([, f]) => f.extension === extension);
+  return (entry?.[0] as FleetFormat | undefined) ?? "markdown";
+}